@@ -0,0 +1,101 @@
+use async_trait::async_trait;
+use reqwest::RequestBuilder;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+use crate::auth::oauth::{Endpoints, LoginState, OAuthProvider, ProviderConfig, Tokens, UserProfile};
+use crate::error::APIError;
+
+const GITHUB_AUTH_URL: &str = "https://github.com/login/oauth/authorize";
+const GITHUB_TOKEN_URL: &str = "https://github.com/login/oauth/access_token";
+const GITHUB_USERINFO_URL: &str = "https://api.github.com/user";
+
+/// GitHub's API rejects requests without a `User-Agent`.
+const USER_AGENT: &str = "portfolio-backend";
+
+#[derive(Deserialize)]
+struct GitHubUser {
+    login: String,
+    name: Option<String>,
+    avatar_url: String,
+}
+
+#[derive(Deserialize)]
+struct GitHubEmail {
+    email: String,
+    primary: bool,
+    verified: bool,
+}
+
+/// Login with GitHub, configured by the `GITHUB_*` variables. The profile's
+/// public email is optional and unverified, so the primary verified address
+/// is read from `{userinfo_url}/emails` instead.
+pub struct GitHub {
+    config: ProviderConfig,
+}
+
+impl GitHub {
+    pub fn new(config: ProviderConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn endpoints() -> Endpoints {
+        Endpoints {
+            auth_url: GITHUB_AUTH_URL.to_string(),
+            token_url: GITHUB_TOKEN_URL.to_string(),
+            userinfo_url: GITHUB_USERINFO_URL.to_string(),
+        }
+    }
+
+    pub fn from_env() -> Option<Self> {
+        ProviderConfig::from_env("GITHUB", Self::endpoints()).map(Self::new)
+    }
+}
+
+async fn fetch<T: DeserializeOwned>(request: RequestBuilder, access_token: &str) -> Result<T, APIError> {
+    request
+        .bearer_auth(access_token)
+        .header(reqwest::header::USER_AGENT, USER_AGENT)
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|_| APIError::InternalServerError)?
+        .json()
+        .await
+        .map_err(|_| APIError::InternalServerError)
+}
+
+#[async_trait]
+impl OAuthProvider for GitHub {
+    fn name(&self) -> &str {
+        "github"
+    }
+
+    fn config(&self) -> &ProviderConfig {
+        &self.config
+    }
+
+    fn scope(&self) -> &'static str {
+        "read:user user:email"
+    }
+
+    async fn user_profile(&self, tokens: &Tokens, _login: &LoginState) -> Result<UserProfile, APIError> {
+        let access_token = tokens.access_token.as_str();
+        let client = reqwest::Client::new();
+        let userinfo_url = self.config.endpoints.userinfo_url.trim_end_matches('/');
+        let user: GitHubUser = fetch(client.get(userinfo_url), access_token).await?;
+        let emails: Vec<GitHubEmail> = fetch(client.get(format!("{}/emails", userinfo_url)), access_token).await?;
+
+        let email = emails
+            .into_iter()
+            .find(|email| email.primary && email.verified)
+            .ok_or(APIError::Unauthorized)?;
+
+        Ok(UserProfile {
+            email: email.email,
+            name: user.name.unwrap_or(user.login),
+            picture: user.avatar_url,
+        })
+    }
+}
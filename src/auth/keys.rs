@@ -0,0 +1,29 @@
+use std::env;
+
+use jsonwebtoken::{DecodingKey, EncodingKey};
+
+use crate::error::APIError;
+
+/// Audiences of the tokens signed with [`secret`] besides access tokens.
+/// Access tokens carry none, so a token with one of these never passes as a
+/// login, nor as a token of the other kind.
+pub mod audience {
+    /// Preview links
+    pub const PREVIEW: &str = "preview";
+    /// State cookies of an OAuth login
+    pub const OAUTH_STATE: &str = "oauth-state";
+}
+
+/// `JWT_SECRET`, which signs every token and keys the CSRF tokens. Missing
+/// it is a misconfiguration, so it is a 500.
+pub fn secret() -> Result<String, APIError> {
+    env::var("JWT_SECRET").map_err(|_| APIError::InternalServerError)
+}
+
+pub fn encoding_key() -> Result<EncodingKey, APIError> {
+    Ok(EncodingKey::from_secret(secret()?.as_bytes()))
+}
+
+pub fn decoding_key() -> Result<DecodingKey, APIError> {
+    Ok(DecodingKey::from_secret(secret()?.as_bytes()))
+}
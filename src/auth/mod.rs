@@ -1,2 +1,8 @@
+pub mod github;
 pub mod google;
+pub mod keys;
+pub mod oauth;
+pub mod oidc;
+pub mod preview;
+pub mod session;
 pub mod user;
@@ -0,0 +1,342 @@
+use std::collections::HashMap;
+use std::env;
+use std::sync::Arc;
+
+use actix_web::cookie::{Cookie, SameSite, time};
+use actix_web::http::header;
+use actix_web::{HttpRequest, HttpResponse, Result, get, web};
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use chrono::{Duration, Utc};
+use jsonwebtoken::{Header, Validation, decode, encode};
+use serde::{Deserialize, Serialize};
+use reqwest::Url;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::auth::github::GitHub;
+use crate::auth::google;
+use crate::auth::keys::{self, audience};
+use crate::auth::oidc::Oidc;
+use crate::auth::session::SessionMode;
+use crate::error::APIError;
+use crate::repository::Store;
+use crate::services::session_service;
+
+/// Cookie that carries the [`LoginState`] from the login redirect to the callback.
+pub const STATE_COOKIE: &str = "oauth_state";
+
+/// How long the user has to complete the provider's login.
+const STATE_TTL: Duration = Duration::minutes(10);
+
+/// What the callback of a login needs to verify: the `state` sent to the
+/// provider, the PKCE `code_verifier` whose challenge was sent with it and,
+/// for OpenID Connect, the `nonce` the ID token must carry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoginState {
+    pub state: String,
+    pub verifier: String,
+    pub nonce: String,
+    pub aud: String,
+    pub exp: usize,
+}
+
+impl LoginState {
+    /// A fresh random state, PKCE verifier and nonce.
+    pub fn generate() -> Self {
+        Self {
+            state: Uuid::new_v4().simple().to_string(),
+            // 64 characters, within the 43 to 128 RFC 7636 allows
+            verifier: format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple()),
+            nonce: Uuid::new_v4().simple().to_string(),
+            aud: audience::OAUTH_STATE.to_string(),
+            exp: (Utc::now() + STATE_TTL).timestamp() as usize,
+        }
+    }
+
+    /// The S256 `code_challenge` of the verifier.
+    pub fn challenge(&self) -> String {
+        URL_SAFE_NO_PAD.encode(Sha256::digest(self.verifier.as_bytes()))
+    }
+
+    /// The signed state as an HttpOnly cookie scoped to `path`, where the
+    /// callback is served.
+    pub fn cookie(&self, path: &str) -> Result<Cookie<'static>, APIError> {
+        let token = encode(&Header::default(), self, &keys::encoding_key()?)
+            .map_err(|_| APIError::InternalServerError)?;
+
+        Ok(Cookie::build(STATE_COOKIE, token)
+            .path(path.to_string())
+            .http_only(true)
+            .secure(true)
+            // Lax, so the cookie is sent on the provider's redirect back
+            .same_site(SameSite::Lax)
+            .max_age(time::Duration::seconds(STATE_TTL.num_seconds()))
+            .finish())
+    }
+
+    /// Checks the callback's `state` against the state cookie of `req`.
+    /// A missing cookie or parameter is 400; a forged, expired or
+    /// mismatched state is 401.
+    pub fn verify(req: &HttpRequest, state: Option<&str>) -> Result<Self, APIError> {
+        let cookie = req
+            .cookie(STATE_COOKIE)
+            .ok_or_else(|| APIError::Validation("missing OAuth state cookie; start the login again".to_string()))?;
+        let state = state.ok_or_else(|| APIError::Validation("missing state parameter".to_string()))?;
+
+        let mut validation = Validation::default();
+        validation.set_audience(&[audience::OAUTH_STATE]);
+        let expected = decode::<LoginState>(cookie.value(), &keys::decoding_key()?, &validation)
+            .map(|data| data.claims)
+            .map_err(|_| APIError::Unauthorized)?;
+
+        if expected.state != state {
+            return Err(APIError::Unauthorized);
+        }
+        Ok(expected)
+    }
+}
+
+/// Expires the state cookie once the login is complete, so it cannot be replayed.
+pub fn clear_cookie(path: &str) -> Cookie<'static> {
+    let mut cookie = Cookie::build(STATE_COOKIE, "").path(path.to_string()).finish();
+    cookie.make_removal();
+    cookie
+}
+
+/// The user a provider vouches for.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct UserProfile {
+    pub email: String,
+    pub name: String,
+    pub picture: String,
+}
+
+/// Endpoints of a provider's OAuth 2.0 authorization code flow.
+#[derive(Debug, Clone)]
+pub struct Endpoints {
+    pub auth_url: String,
+    pub token_url: String,
+    /// Where the profile is read with the access token, by providers
+    /// without ID tokens
+    pub userinfo_url: String,
+}
+
+/// Client credentials and endpoints of a provider, read from
+/// `{PREFIX}_CLIENT_ID`, `{PREFIX}_CLIENT_SECRET` and `{PREFIX}_REDIRECT_URI`.
+/// The endpoints can be overridden with `{PREFIX}_AUTH_URL`,
+/// `{PREFIX}_TOKEN_URL` and `{PREFIX}_USERINFO_URL`, e.g. to log in against
+/// a mock server.
+#[derive(Debug, Clone)]
+pub struct ProviderConfig {
+    pub client_id: String,
+    pub client_secret: String,
+    pub redirect_uri: String,
+    pub endpoints: Endpoints,
+}
+
+impl ProviderConfig {
+    /// `None` unless the provider's credentials are all set.
+    pub fn from_env(prefix: &str, defaults: Endpoints) -> Option<Self> {
+        let var = |name: &str| env::var(format!("{}_{}", prefix, name)).ok();
+        Some(Self {
+            client_id: var("CLIENT_ID")?,
+            client_secret: var("CLIENT_SECRET")?,
+            redirect_uri: var("REDIRECT_URI")?,
+            endpoints: Endpoints {
+                auth_url: var("AUTH_URL").unwrap_or(defaults.auth_url),
+                token_url: var("TOKEN_URL").unwrap_or(defaults.token_url),
+                userinfo_url: var("USERINFO_URL").unwrap_or(defaults.userinfo_url),
+            },
+        })
+    }
+}
+
+/// What a provider's token endpoint grants for an authorization code.
+#[derive(Debug, Clone)]
+pub struct Tokens {
+    pub access_token: String,
+    /// Signed claims about the user, from OpenID Connect providers
+    pub id_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    /// Missing when the provider rejects the code with a 200, as GitHub does
+    access_token: Option<String>,
+    id_token: Option<String>,
+}
+
+/// A service admins can log in with through the OAuth 2.0 authorization code
+/// flow with PKCE. The authorize URL and token exchange follow RFC 6749 and
+/// only differ in [`scope`](Self::scope); profiles are provider-specific.
+#[async_trait]
+pub trait OAuthProvider: Send + Sync {
+    /// Name in the routes, e.g. `google` in `/v1/auth/google/login`
+    fn name(&self) -> &str;
+    fn config(&self) -> &ProviderConfig;
+    /// Space-separated scopes that grant access to the user's email. With
+    /// `openid`, the login is OpenID Connect and sends a `nonce`.
+    fn scope(&self) -> &'static str;
+
+    /// The provider's consent screen for `login`.
+    fn authorize_url(&self, login: &LoginState) -> Result<String, APIError> {
+        let config = self.config();
+        let challenge = login.challenge();
+        let mut params = vec![
+            ("client_id", config.client_id.as_str()),
+            ("redirect_uri", config.redirect_uri.as_str()),
+            ("response_type", "code"),
+            ("scope", self.scope()),
+            ("state", login.state.as_str()),
+            ("code_challenge", challenge.as_str()),
+            ("code_challenge_method", "S256"),
+        ];
+        if self.scope().split(' ').any(|scope| scope == "openid") {
+            params.push(("nonce", login.nonce.as_str()));
+        }
+
+        let url = Url::parse_with_params(&config.endpoints.auth_url, params).map_err(|_| APIError::InternalServerError)?;
+        Ok(url.into())
+    }
+
+    /// Trades the callback's `code` for tokens. A code the provider rejects is 401.
+    async fn exchange_code(&self, code: &str, verifier: &str) -> Result<Tokens, APIError> {
+        let config = self.config();
+        let params = [
+            ("client_id", config.client_id.as_str()),
+            ("client_secret", config.client_secret.as_str()),
+            ("code", code),
+            ("grant_type", "authorization_code"),
+            ("redirect_uri", config.redirect_uri.as_str()),
+            ("code_verifier", verifier),
+        ];
+
+        let response = reqwest::Client::new()
+            .post(&config.endpoints.token_url)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .form(&params)
+            .send()
+            .await
+            .map_err(|_| APIError::InternalServerError)?;
+        if !response.status().is_success() {
+            return Err(APIError::Unauthorized);
+        }
+
+        let token: TokenResponse = response.json().await.map_err(|_| APIError::InternalServerError)?;
+        Ok(Tokens {
+            access_token: token.access_token.ok_or(APIError::Unauthorized)?,
+            id_token: token.id_token,
+        })
+    }
+
+    /// The profile of the user who granted `tokens` during `login`.
+    async fn user_profile(&self, tokens: &Tokens, login: &LoginState) -> Result<UserProfile, APIError>;
+}
+
+/// The providers admins can log in with, by [`name`](OAuthProvider::name).
+///
+/// ```ignore
+/// App::new().app_data(web::Data::new(Providers::default().with(GitHub::new(config))))
+/// ```
+#[derive(Clone, Default)]
+pub struct Providers(HashMap<String, Arc<dyn OAuthProvider>>);
+
+impl Providers {
+    pub fn with(mut self, provider: impl OAuthProvider + 'static) -> Self {
+        self.0.insert(provider.name().to_string(), Arc::new(provider));
+        self
+    }
+
+    /// Every provider with well-known endpoints whose credentials are set in
+    /// the environment.
+    pub fn from_env() -> Self {
+        let mut providers = Self::default();
+        if let Some(google) = google::from_env() {
+            providers = providers.with(google);
+        }
+        if let Some(github) = GitHub::from_env() {
+            providers = providers.with(github);
+        }
+        providers
+    }
+
+    /// Adds the OpenID Connect issuers named in `OIDC_PROVIDERS`, whose
+    /// endpoints are discovered now. Issuers that cannot be reached are
+    /// logged and left out.
+    pub async fn with_discovered(mut self) -> Self {
+        let names = env::var("OIDC_PROVIDERS").unwrap_or_default();
+        for name in names.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+            match Oidc::from_env(name).await {
+                Ok(Some(provider)) => self = self.with(provider),
+                Ok(None) => tracing::warn!("⚠️ OIDC provider `{}` is missing its credentials", name),
+                Err(e) => tracing::error!("❌ Failed to discover OIDC provider `{}`: {:?}", name, e),
+            }
+        }
+        self
+    }
+
+    /// The providers registered as app data, or those [from the environment](Self::from_env).
+    pub fn of(req: &HttpRequest) -> Self {
+        req.app_data::<web::Data<Providers>>()
+            .map(|providers| providers.get_ref().clone())
+            .unwrap_or_else(Self::from_env)
+    }
+
+    fn get(&self, name: &str) -> Result<Arc<dyn OAuthProvider>, APIError> {
+        self.0.get(name).cloned().ok_or(APIError::NotFound)
+    }
+}
+
+/// Where the state cookie is sent: the provider's callback, under the `/v1` scope.
+fn cookie_path(provider: &str) -> String {
+    format!("/v1/auth/{}", provider)
+}
+
+#[derive(Deserialize)]
+pub struct AuthRequest {
+    pub code: String,
+    pub state: Option<String>,
+}
+
+/// Redirects to the provider's consent screen with a fresh `state` and PKCE
+/// challenge, which the callback checks against the state cookie set here.
+#[get("/auth/{provider}/login")]
+async fn oauth_login(req: HttpRequest, provider: web::Path<String>) -> Result<HttpResponse, APIError> {
+    let provider = Providers::of(&req).get(&provider)?;
+    let login = LoginState::generate();
+
+    Ok(HttpResponse::Found()
+        .append_header((header::LOCATION, provider.authorize_url(&login)?))
+        .cookie(login.cookie(&cookie_path(provider.name()))?)
+        .finish())
+}
+
+/// Completes the login and hands the tokens of a new session over the
+/// [`SessionMode`], when the provider vouches for `ALLOWED_EMAIL`.
+#[get("/auth/{provider}/callback")]
+async fn oauth_callback(
+    req: HttpRequest,
+    store: web::Data<Store>,
+    provider: web::Path<String>,
+    web::Query(info): web::Query<AuthRequest>,
+) -> Result<HttpResponse, APIError> {
+    let provider = Providers::of(&req).get(&provider)?;
+    let login = LoginState::verify(&req, info.state.as_deref())?;
+
+    let tokens = provider.exchange_code(&info.code, &login.verifier).await?;
+    let profile = provider.user_profile(&tokens, &login).await?;
+
+    let allowed = env::var("ALLOWED_EMAIL").map_err(|_| APIError::InternalServerError)?;
+    if !profile.email.eq_ignore_ascii_case(&allowed) {
+        return Err(APIError::Unauthorized);
+    }
+
+    let tokens = session_service::start(&store, profile).await?;
+    SessionMode::of(&req).log_in(HttpResponse::Ok().cookie(clear_cookie(&cookie_path(provider.name()))), tokens)
+}
+
+pub fn routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(oauth_login).service(oauth_callback);
+}
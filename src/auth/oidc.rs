@@ -0,0 +1,206 @@
+use std::env;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use jsonwebtoken::jwk::{Jwk, JwkSet};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode, decode_header};
+use serde::Deserialize;
+
+use crate::auth::oauth::{Endpoints, LoginState, OAuthProvider, ProviderConfig, Tokens, UserProfile};
+use crate::error::APIError;
+
+/// How long a fetched JWKS is trusted. A token signed with a key id that is
+/// not in the cached set fetches it again sooner, which picks up rotated keys.
+const JWKS_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Algorithms ID tokens may be signed with. Symmetric ones are left out, as
+/// they would let anyone who knows the client secret sign tokens.
+const ALGORITHMS: &[Algorithm] = &[
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::PS384,
+    Algorithm::PS512,
+    Algorithm::ES256,
+    Algorithm::ES384,
+    Algorithm::EdDSA,
+];
+
+/// The parts of an issuer's `/.well-known/openid-configuration` a login needs.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Discovery {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: String,
+    #[serde(default)]
+    pub userinfo_endpoint: Option<String>,
+}
+
+impl Discovery {
+    pub fn endpoints(&self) -> Endpoints {
+        Endpoints {
+            auth_url: self.authorization_endpoint.clone(),
+            token_url: self.token_endpoint.clone(),
+            userinfo_url: self.userinfo_endpoint.clone().unwrap_or_default(),
+        }
+    }
+
+    pub fn metadata(&self) -> Metadata {
+        Metadata {
+            issuers: vec![self.issuer.clone()],
+            jwks_uri: self.jwks_uri.clone(),
+        }
+    }
+}
+
+/// Fetches the discovery document at `url`.
+pub async fn discover(url: &str) -> Result<Discovery, APIError> {
+    reqwest::get(url)
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|_| APIError::InternalServerError)?
+        .json()
+        .await
+        .map_err(|_| APIError::InternalServerError)
+}
+
+/// Where an issuer's ID tokens come from and the keys they are signed with.
+#[derive(Debug, Clone)]
+pub struct Metadata {
+    /// The `iss` values its ID tokens may carry
+    pub issuers: Vec<String>,
+    pub jwks_uri: String,
+}
+
+#[derive(Deserialize)]
+struct IdClaims {
+    email: Option<String>,
+    #[serde(default)]
+    email_verified: bool,
+    nonce: Option<String>,
+    name: Option<String>,
+    picture: Option<String>,
+}
+
+/// Login with an OpenID Connect issuer. The profile is read from the ID
+/// token, which must be signed with a key from the issuer's JWKS, be issued
+/// for this client by the issuer, carry the login's `nonce` and vouch for a
+/// verified email.
+///
+/// Issuers other than [Google](crate::auth::google) are named in
+/// `OIDC_PROVIDERS` and configured by `{NAME}_DISCOVERY_URL` along with the
+/// credentials of [`ProviderConfig`].
+pub struct Oidc {
+    name: String,
+    config: ProviderConfig,
+    metadata: Metadata,
+    jwks: Mutex<Option<(JwkSet, Instant)>>,
+}
+
+impl Oidc {
+    pub fn new(name: impl Into<String>, config: ProviderConfig, metadata: Metadata) -> Self {
+        Self {
+            name: name.into(),
+            config,
+            metadata,
+            jwks: Mutex::new(None),
+        }
+    }
+
+    /// The issuer `name`, discovered from `{NAME}_DISCOVERY_URL`. `None`
+    /// when it or the credentials are not set.
+    pub async fn from_env(name: &str) -> Result<Option<Self>, APIError> {
+        let prefix = name.to_ascii_uppercase().replace('-', "_");
+        let Ok(url) = env::var(format!("{}_DISCOVERY_URL", prefix)) else {
+            return Ok(None);
+        };
+        let discovery = discover(&url).await?;
+        Ok(ProviderConfig::from_env(&prefix, discovery.endpoints()).map(|config| Self::new(name, config, discovery.metadata())))
+    }
+
+    /// The key `kid` from the cached JWKS, unless it is stale or lacks the key.
+    fn cached_key(&self, kid: Option<&str>) -> Option<Jwk> {
+        let jwks = self.jwks.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        jwks.as_ref()
+            .filter(|(_, fetched)| fetched.elapsed() < JWKS_TTL)
+            .and_then(|(keys, _)| find(keys, kid).cloned())
+    }
+
+    async fn key(&self, kid: Option<&str>) -> Result<DecodingKey, APIError> {
+        let jwk = match self.cached_key(kid) {
+            Some(jwk) => jwk,
+            None => {
+                let keys: JwkSet = reqwest::get(&self.metadata.jwks_uri)
+                    .await
+                    .and_then(|response| response.error_for_status())
+                    .map_err(|_| APIError::InternalServerError)?
+                    .json()
+                    .await
+                    .map_err(|_| APIError::InternalServerError)?;
+                let jwk = find(&keys, kid).cloned();
+                *self.jwks.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some((keys, Instant::now()));
+                jwk.ok_or(APIError::Unauthorized)?
+            }
+        };
+        DecodingKey::from_jwk(&jwk).map_err(|_| APIError::Unauthorized)
+    }
+
+    /// Verifies `id_token` and reads the profile from it.
+    pub async fn verify(&self, id_token: &str, nonce: &str) -> Result<UserProfile, APIError> {
+        let header = decode_header(id_token).map_err(|_| APIError::Unauthorized)?;
+        if !ALGORITHMS.contains(&header.alg) {
+            return Err(APIError::Unauthorized);
+        }
+        let key = self.key(header.kid.as_deref()).await?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&self.metadata.issuers);
+        validation.set_audience(&[&self.config.client_id]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud"]);
+        let claims = decode::<IdClaims>(id_token, &key, &validation)
+            .map_err(|_| APIError::Unauthorized)?
+            .claims;
+
+        if claims.nonce.as_deref() != Some(nonce) || !claims.email_verified {
+            return Err(APIError::Unauthorized);
+        }
+        let email = claims.email.ok_or(APIError::Unauthorized)?;
+        Ok(UserProfile {
+            name: claims.name.unwrap_or_else(|| email.clone()),
+            picture: claims.picture.unwrap_or_default(),
+            email,
+        })
+    }
+}
+
+/// The key `kid`, or the only key of the set when the token names none.
+fn find<'a>(keys: &'a JwkSet, kid: Option<&str>) -> Option<&'a Jwk> {
+    match kid {
+        Some(kid) => keys.find(kid),
+        None if keys.keys.len() == 1 => keys.keys.first(),
+        None => None,
+    }
+}
+
+#[async_trait]
+impl OAuthProvider for Oidc {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn config(&self) -> &ProviderConfig {
+        &self.config
+    }
+
+    fn scope(&self) -> &'static str {
+        "openid email profile"
+    }
+
+    async fn user_profile(&self, tokens: &Tokens, login: &LoginState) -> Result<UserProfile, APIError> {
+        let id_token = tokens.id_token.as_deref().ok_or(APIError::Unauthorized)?;
+        self.verify(id_token, &login.nonce).await
+    }
+}
@@ -0,0 +1,94 @@
+use std::env;
+
+use actix_web::{FromRequest, HttpRequest, dev::Payload, web};
+use chrono::{DateTime, Duration, Utc};
+use futures::future::{Ready, ready};
+use jsonwebtoken::{Header, Validation, decode, encode};
+use serde::{Deserialize, Serialize};
+
+use crate::auth::keys::{self, audience};
+use crate::error::APIError;
+use crate::models::status::Viewer;
+
+/// Claims of a token that lets anyone holding it read one unpublished record.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreviewClaims {
+    /// Table of the record, which is specific to its site
+    pub collection: String,
+    pub id: String,
+    pub aud: String,
+    pub exp: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PreviewToken {
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// How long preview links stay valid: `PREVIEW_TTL_SECS`, one hour by default.
+pub fn ttl() -> Duration {
+    env::var("PREVIEW_TTL_SECS")
+        .ok()
+        .and_then(|secs| secs.parse().ok())
+        .map(Duration::seconds)
+        .unwrap_or_else(|| Duration::hours(1))
+}
+
+/// Signs a preview token for the record `id` in `table`.
+pub fn mint(table: &str, id: &str, ttl: Duration) -> Result<PreviewToken, APIError> {
+    let expires_at = Utc::now() + ttl;
+    let claims = PreviewClaims {
+        collection: table.to_string(),
+        id: id.to_string(),
+        aud: audience::PREVIEW.to_string(),
+        exp: expires_at.timestamp() as usize,
+    };
+
+    let token = encode(&Header::default(), &claims, &keys::encoding_key()?)
+        .map_err(|_| APIError::InternalServerError)?;
+    Ok(PreviewToken { token, expires_at })
+}
+
+pub fn verify(token: &str) -> Result<PreviewClaims, APIError> {
+    let mut validation = Validation::default();
+    validation.set_audience(&[audience::PREVIEW]);
+
+    decode::<PreviewClaims>(token, &keys::decoding_key()?, &validation)
+        .map(|data| data.claims)
+        .map_err(|_| APIError::Unauthorized)
+}
+
+/// The `?preview=<token>` of a public read. An invalid or expired token is 401.
+pub struct Preview(pub Option<PreviewClaims>);
+
+#[derive(Deserialize)]
+struct PreviewQuery {
+    preview: Option<String>,
+}
+
+impl FromRequest for Preview {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let token = web::Query::<PreviewQuery>::from_query(req.query_string())
+            .ok()
+            .and_then(|query| query.into_inner().preview);
+
+        ready(match token {
+            Some(token) => verify(&token).map(|claims| Preview(Some(claims))).map_err(Into::into),
+            None => Ok(Preview(None)),
+        })
+    }
+}
+
+impl Preview {
+    /// Lets `viewer` see the record `id` in `table` when the token was minted for it.
+    pub fn viewer_for(&self, viewer: Viewer, table: &str, id: &str) -> Viewer {
+        match &self.0 {
+            Some(claims) if claims.collection == table && claims.id == id && viewer == Viewer::Public => Viewer::Preview,
+            _ => viewer,
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use std::env;
+
+use actix_web::cookie::{Cookie, SameSite, time};
+use actix_web::http::{StatusCode, header};
+use actix_web::{HttpRequest, HttpResponse, HttpResponseBuilder, web};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use subtle::ConstantTimeEq;
+
+use crate::auth::keys;
+use crate::error::APIError;
+use crate::services::session_service::SessionTokens;
+
+/// Cookie that carries the access token in [cookie mode](SessionMode::Cookie).
+pub const SESSION_COOKIE: &str = "session";
+
+/// Cookie that carries the refresh token, only sent to the `/v1/auth` routes.
+pub const REFRESH_COOKIE: &str = "refresh_token";
+
+/// Cookie the dashboard reads the CSRF token from, to send it back in [`CSRF_HEADER`].
+pub const CSRF_COOKIE: &str = "csrf_token";
+
+pub const CSRF_HEADER: &str = "X-CSRF-Token";
+
+const REFRESH_PATH: &str = "/v1/auth";
+
+/// How a login hands its tokens to the client.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum SessionMode {
+    /// Answer with the tokens as JSON, for clients that send a bearer token
+    #[default]
+    Bearer,
+    /// Keep the tokens in HttpOnly cookies and redirect to the admin dashboard,
+    /// so they are never exposed to its scripts. The dashboard must be served
+    /// from the API's origin: it reads the host-only [`CSRF_COOKIE`], and
+    /// cookie-authenticated writes from any other `Origin` are rejected.
+    Cookie { admin_url: String },
+}
+
+impl SessionMode {
+    /// [`Cookie`](Self::Cookie) when `SESSION_MODE` is `cookie` and
+    /// `ADMIN_URL` is set, [`Bearer`](Self::Bearer) otherwise.
+    pub fn from_env() -> Self {
+        match (env::var("SESSION_MODE").as_deref(), env::var("ADMIN_URL")) {
+            (Ok("cookie"), Ok(admin_url)) => SessionMode::Cookie { admin_url },
+            _ => SessionMode::Bearer,
+        }
+    }
+
+    /// The mode registered as app data, or the one [from the environment](Self::from_env).
+    pub fn of(req: &HttpRequest) -> Self {
+        req.app_data::<web::Data<SessionMode>>()
+            .map(|mode| mode.get_ref().clone())
+            .unwrap_or_else(Self::from_env)
+    }
+
+    /// Finishes `response` to a login with `tokens`: as JSON, or as cookies
+    /// and a redirect to the dashboard.
+    pub fn log_in(&self, response: &mut HttpResponseBuilder, tokens: SessionTokens) -> Result<HttpResponse, APIError> {
+        match self {
+            SessionMode::Bearer => Ok(response.json(tokens)),
+            SessionMode::Cookie { admin_url } => {
+                for cookie in session_cookies(&tokens)? {
+                    response.cookie(cookie);
+                }
+                Ok(response
+                    .status(StatusCode::FOUND)
+                    .append_header((header::LOCATION, admin_url.as_str()))
+                    .finish())
+            }
+        }
+    }
+}
+
+/// The CSRF token of the session `sid`. It is keyed with the [secret](keys::secret),
+/// so a cookie planted by another site, e.g. a sibling subdomain, cannot match it.
+pub fn csrf_token(sid: &str) -> Result<String, APIError> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(keys::secret()?.as_bytes()).map_err(|_| APIError::InternalServerError)?;
+    mac.update(sid.as_bytes());
+    Ok(hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Checks that a request authenticated by cookie comes from the API's own
+/// origin, when the browser says, and sends the CSRF token of the session
+/// `sid`. Only safe methods may go without them.
+pub fn verify_csrf(req: &HttpRequest, sid: &str) -> Result<(), APIError> {
+    if req.method().is_safe() {
+        return Ok(());
+    }
+
+    if let Some(origin) = req.headers().get(header::ORIGIN) {
+        let info = req.connection_info();
+        let own = format!("{}://{}", info.scheme(), info.host());
+        if origin.as_bytes() != own.as_bytes() {
+            return Err(APIError::Forbidden("cross-origin request".to_string()));
+        }
+    }
+
+    let expected = csrf_token(sid)?;
+    let sent = req.headers().get(CSRF_HEADER).map(|value| value.as_bytes()).unwrap_or_default();
+    if !bool::from(sent.ct_eq(expected.as_bytes())) {
+        return Err(APIError::Forbidden(format!("missing or invalid {}", CSRF_HEADER)));
+    }
+    Ok(())
+}
+
+fn cookie(name: &'static str, value: String, path: &'static str, expires_at: DateTime<Utc>) -> Cookie<'static> {
+    Cookie::build(name, value)
+        .path(path)
+        .http_only(true)
+        .secure(true)
+        .same_site(SameSite::Strict)
+        .max_age(time::Duration::seconds((expires_at - Utc::now()).num_seconds().max(0)))
+        .finish()
+}
+
+/// The cookies that carry `tokens` and the CSRF token of their session.
+pub fn session_cookies(tokens: &SessionTokens) -> Result<Vec<Cookie<'static>>, APIError> {
+    let mut csrf = cookie(CSRF_COOKIE, csrf_token(&tokens.session_id)?, "/", tokens.refresh_expires_at);
+    // The dashboard reads it to echo it back
+    csrf.set_http_only(false);
+
+    Ok(vec![
+        cookie(SESSION_COOKIE, tokens.token.clone(), "/", tokens.expires_at),
+        cookie(REFRESH_COOKIE, tokens.refresh_token.clone(), REFRESH_PATH, tokens.refresh_expires_at),
+        csrf,
+    ])
+}
+
+/// Expires every session cookie, when the session ends.
+pub fn clear_cookies() -> Vec<Cookie<'static>> {
+    [(SESSION_COOKIE, "/"), (REFRESH_COOKIE, REFRESH_PATH), (CSRF_COOKIE, "/")]
+        .into_iter()
+        .map(|(name, path)| {
+            let mut cookie = Cookie::build(name, "").path(path).finish();
+            cookie.make_removal();
+            cookie
+        })
+        .collect()
+}
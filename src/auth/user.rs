@@ -1,50 +1,105 @@
-use actix_web::FromRequest;
-use futures::future::{Ready, ready};
-use serde::Serialize;
-use jsonwebtoken::{DecodingKey, Validation, decode};
+use actix_web::{FromRequest, web};
+use chrono::Duration;
+use futures::future::LocalBoxFuture;
+use serde::{Deserialize, Serialize};
+use jsonwebtoken::{Header, Validation, decode, encode};
 
-use crate::{auth::google::Claims, error::APIError};
+use crate::{error::APIError, models::Record, models::session::Session, models::status::Viewer, repository::Store};
+use crate::auth::keys;
+use crate::auth::session::{SESSION_COOKIE, verify_csrf};
+use crate::services::session_service;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Claims {
+    pub email: String,
+    pub name: String,
+    pub picture: String,
+    /// Id of the token, which [revokes](session_service::is_revoked) it
+    pub jti: String,
+    /// Id of the [`Session`] the token was issued for
+    pub sid: String,
+    pub exp: usize,
+}
+
+/// How long an access token lasts: `ACCESS_TOKEN_TTL_SECS`, 15 minutes by
+/// default. The session's refresh token gets a new one.
+pub fn access_ttl() -> Duration {
+    std::env::var("ACCESS_TOKEN_TTL_SECS")
+        .ok()
+        .and_then(|secs| secs.parse().ok())
+        .map(Duration::seconds)
+        .unwrap_or_else(|| Duration::minutes(15))
+}
+
+/// Signs the access token `session` currently has.
+pub fn issue(session: &Session) -> Result<String, APIError> {
+    let claims = Claims {
+        email: session.email.clone(),
+        name: session.name.clone(),
+        picture: session.picture.clone(),
+        jti: session.access_jti.clone(),
+        sid: session.id().ok_or(APIError::InternalServerError)?.to_string(),
+        exp: session.access_expires_at.timestamp() as usize,
+    };
+
+    encode(&Header::default(), &claims, &keys::encoding_key()?)
+        .map_err(|_| APIError::InternalServerError)
+}
 
 #[derive(Debug, Serialize)]
 pub struct AuthenticatedUser(pub Claims);
 
 impl FromRequest for AuthenticatedUser {
     type Error = actix_web::Error;
-    type Future = Ready<Result<Self, Self::Error>>;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
 
     fn from_request(req: &actix_web::HttpRequest, _: &mut actix_web::dev::Payload) -> Self::Future {
-        // Extract token from Authorization header
-        let token = req
+        // Extract token from Authorization header, or else the session cookie
+        let bearer = req
             .headers()
             .get("Authorization")
             .and_then(|h| h.to_str().ok())
             .and_then(|h| h.strip_prefix("Bearer "))
             .map(|t| t.to_string());
+        let from_cookie = bearer.is_none();
+        let token = bearer.or_else(|| req.cookie(SESSION_COOKIE).map(|cookie| cookie.value().to_string()));
+        let store = req.app_data::<web::Data<Store>>().cloned();
+        let req = req.clone();
 
-        if let Some(token) = token {
-            // Get JWT secret from environment
-            let key = match std::env::var("JWT_SECRET") {
-                Ok(secret) => secret,
-                Err(_) => {
-                    return ready(Err(APIError::InternalServerError.into()));
-                }
-            };
-
+        Box::pin(async move {
+            let token = token.ok_or(APIError::Unauthorized)?;
             // Validate and decode the JWT token
-            match decode::<Claims>(
-                &token,
-                &DecodingKey::from_secret(key.as_bytes()),
-                &Validation::default(),
-            ) {
-                Ok(token_data) => {
-                    return ready(Ok(AuthenticatedUser(token_data.claims)));
-                }
-                Err(_) => {
-                    return ready(Err(APIError::Unauthorized.into()));
-                }
+            let claims = decode::<Claims>(&token, &keys::decoding_key()?, &Validation::default())
+                .map_err(|_| APIError::Unauthorized)?
+                .claims;
+
+            // Browsers attach cookies to requests other sites make, too
+            if from_cookie {
+                verify_csrf(&req, &claims.sid)?;
+            }
+
+            // A signature is not enough once the token has been revoked
+            let store = store.ok_or(APIError::InternalServerError)?;
+            if session_service::is_revoked(&store, &claims.jti).await? {
+                return Err(APIError::Unauthorized.into());
             }
-        }
+            Ok(AuthenticatedUser(claims))
+        })
+    }
+}
+
+/// [`Viewer::Admin`] for requests with a valid access token, [`Viewer::Public`] otherwise.
+impl FromRequest for Viewer {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
 
-        ready(Err(APIError::Unauthorized.into()))
+    fn from_request(req: &actix_web::HttpRequest, payload: &mut actix_web::dev::Payload) -> Self::Future {
+        let user = AuthenticatedUser::from_request(req, payload);
+        Box::pin(async move {
+            Ok(match user.await {
+                Ok(_) => Viewer::Admin,
+                Err(_) => Viewer::Public,
+            })
+        })
     }
 }
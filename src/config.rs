@@ -1,38 +1,116 @@
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
 use once_cell::sync::OnceCell;
 use std::env;
+use std::time::Duration;
 use surrealdb::{
     Surreal,
-    engine::remote::ws::{Client, Wss},
+    engine::any::{self, Any},
     opt::auth::Root,
 };
 
-static DB: OnceCell<Surreal<Client>> = OnceCell::new();
+use crate::repository::{ReadCache, Store};
 
-pub fn get_db() -> &'static Surreal<Client> {
+static DB: OnceCell<Surreal<Any>> = OnceCell::new();
+
+pub fn get_db() -> &'static Surreal<Any> {
     DB.get().expect("SurrealDB is not initialized")
 }
 
-pub async fn init_db() -> Result<()> {
-    let surreal_uri = env::var("SURREAL_URI").expect("SURREAL_URI must be set");
-    let user = env::var("SURREAL_USER").expect("SURREAL_USER must be set");
-    let pass = env::var("SURREAL_PASS").expect("SURREAL_PASS must be set");
-    let ns = env::var("SURREAL_NS").expect("SURREAL_NS must be set");
-    let db_name = env::var("SURREAL_DB").unwrap_or_else(|_| "portfolio".to_string());
+/// Connects to the engine selected by the scheme of `uri`:
+/// `mem://`, `rocksdb://path`, `ws://host` or `wss://host`.
+/// A URI without a scheme is treated as `wss://` for backwards compatibility.
+pub async fn connect(uri: &str) -> Result<Surreal<Any>> {
+    let endpoint = if uri.contains("://") {
+        uri.to_string()
+    } else {
+        format!("wss://{}", uri)
+    };
+
+    let scheme = endpoint.split("://").next().unwrap_or_default();
+    let remote = match scheme {
+        "mem" => false,
+        "rocksdb" if cfg!(feature = "rocksdb") => false,
+        "rocksdb" => bail!("rocksdb:// requires building with the `rocksdb` feature"),
+        "ws" | "wss" => true,
+        other => bail!("Unsupported SURREAL_URI scheme `{}`", other),
+    };
+
+    let surreal = any::connect(endpoint)
+        .await
+        .with_context(|| format!("Failed to connect to {}", uri))?;
 
-    let surreal = Surreal::new::<Wss>(surreal_uri).await?;
+    let ns = if remote {
+        let user = env::var("SURREAL_USER").context("SURREAL_USER must be set")?;
+        let pass = env::var("SURREAL_PASS").context("SURREAL_PASS must be set")?;
 
-    surreal
-        .signin(Root {
-            username: &user,
-            password: &pass,
-        })
-        .await?;
+        surreal
+            .signin(Root {
+                username: &user,
+                password: &pass,
+            })
+            .await?;
+
+        env::var("SURREAL_NS").context("SURREAL_NS must be set")?
+    } else {
+        env::var("SURREAL_NS").unwrap_or_else(|_| "portfolio".to_string())
+    };
+    let db_name = env::var("SURREAL_DB").unwrap_or_else(|_| "portfolio".to_string());
 
     surreal.use_ns(ns).use_db(db_name).await?;
 
+    Ok(surreal)
+}
+
+pub async fn init_db() -> Result<()> {
+    let surreal_uri = env::var("SURREAL_URI").context("SURREAL_URI must be set")?;
+    let surreal = connect(&surreal_uri).await?;
+
     DB.set(surreal).ok();
 
     tracing::info!("✅ Connected to SurrealDB");
     Ok(())
 }
+
+/// Builds the repository store. `STORE=memory` keeps everything in process,
+/// which is handy for local development without a SurrealDB instance.
+///
+/// Reads are cached in process for `CACHE_TTL_SECS` (default 60, `0` disables
+/// the cache), holding at most `CACHE_MAX_ENTRIES` (default 1000) entries.
+pub async fn init_store() -> Result<Store> {
+    let store = if env::var("STORE").is_ok_and(|store| store == "memory") {
+        tracing::warn!("⚠️ Using in-memory store, data will not be persisted");
+        Store::in_memory()
+    } else {
+        init_db().await?;
+        Store::surreal(get_db().clone())
+    };
+
+    let ttl: u64 = env::var("CACHE_TTL_SECS")
+        .map(|ttl| ttl.parse().context("CACHE_TTL_SECS must be a number of seconds"))
+        .unwrap_or(Ok(60))?;
+    let max_entries: usize = env::var("CACHE_MAX_ENTRIES")
+        .map(|max| max.parse().context("CACHE_MAX_ENTRIES must be a number"))
+        .unwrap_or(Ok(1000))?;
+
+    if ttl == 0 || max_entries == 0 {
+        return Ok(store);
+    }
+    Ok(store.cached(ReadCache::new(Duration::from_secs(ttl), max_entries)))
+}
+
+/// How long deleted records stay in the trash before they are purged:
+/// `TRASH_RETENTION_DAYS`, 30 by default.
+pub fn trash_retention() -> Result<chrono::Duration> {
+    let days: i64 = env::var("TRASH_RETENTION_DAYS")
+        .map(|days| days.parse().context("TRASH_RETENTION_DAYS must be a number of days"))
+        .unwrap_or(Ok(30))?;
+    Ok(chrono::Duration::days(days))
+}
+
+/// How often the publishing scheduler runs: `SCHEDULER_INTERVAL_SECS`, 30 by default.
+pub fn scheduler_interval() -> Result<Duration> {
+    let secs: u64 = env::var("SCHEDULER_INTERVAL_SECS")
+        .map(|secs| secs.parse().context("SCHEDULER_INTERVAL_SECS must be a number of seconds"))
+        .unwrap_or(Ok(30))?;
+    Ok(Duration::from_secs(secs.max(1)))
+}
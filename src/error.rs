@@ -7,15 +7,27 @@ pub enum APIError {
     #[error("Unauthorized")]
     Unauthorized,
 
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
     #[error("Not Found")]
     NotFound,
 
     #[error("Validation Error: {0}")]
     Validation(String),
 
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
     #[error("Database Error: {0}")]
     Database(String),
 
+    #[error("Precondition Failed")]
+    PreconditionFailed,
+
+    #[error("Precondition Required: send If-Match with the ETag of the record")]
+    PreconditionRequired,
+
     #[error("Not Implemented: {0}")]
     NotImplemented(String),
 
@@ -32,9 +44,13 @@ impl ResponseError for APIError {
     fn status_code(&self) -> StatusCode {
         match self {
             APIError::Unauthorized => StatusCode::UNAUTHORIZED,
+            APIError::Forbidden(_) => StatusCode::FORBIDDEN,
             APIError::NotFound => StatusCode::NOT_FOUND,
             APIError::Validation(_) => StatusCode::BAD_REQUEST,
+            APIError::Conflict(_) => StatusCode::CONFLICT,
             APIError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            APIError::PreconditionFailed => StatusCode::PRECONDITION_FAILED,
+            APIError::PreconditionRequired => StatusCode::PRECONDITION_REQUIRED,
             APIError::InternalServerError => StatusCode::INTERNAL_SERVER_ERROR,
             APIError::NotImplemented(_) => StatusCode::NOT_IMPLEMENTED,
         }
@@ -1,8 +1,10 @@
 pub mod auth;
+pub mod clock;
 pub mod config;
 pub mod db;
 pub mod error;
 pub mod models;
+pub mod repository;
 pub mod routes;
 pub mod services;
 pub mod utils;
\ No newline at end of file
@@ -1,16 +1,11 @@
 use actix_web::{App, HttpResponse, HttpServer, Responder, middleware::Logger, web};
 use dotenvy::dotenv;
 use std::env;
+use std::sync::Arc;
+use std::time::Duration;
 use tracing_subscriber::FmtSubscriber;
 
-use crate::error::APIError;
-
-mod auth;
-mod config;
-mod error;
-mod models;
-mod routes;
-mod services;
+use portfolio_backend::{auth::oauth::Providers, auth::session::SessionMode, clock::SystemClock, config, error::APIError, routes, services::{session_service, trash_service}};
 
 async fn root() -> impl Responder {
     HttpResponse::Ok().body("Kishor's Portfolio Backend API")
@@ -30,10 +25,31 @@ async fn main() -> std::io::Result<()> {
     tracing::subscriber::set_global_default(subscriber)
         .expect("Failed to set global tracing subscriber");
 
-    if let Err(e) = config::init_db().await {
-        tracing::error!("❌ Failed to init DB: {:?}", e);
-        std::process::exit(1);
-    }
+    let store = match config::init_store().await {
+        Ok(store) => store,
+        Err(e) => {
+            tracing::error!("❌ Failed to init DB: {:?}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let (retention, schedule_every) = match (config::trash_retention(), config::scheduler_interval()) {
+        (Ok(retention), Ok(every)) => (retention, every),
+        (Err(e), _) | (_, Err(e)) => {
+            tracing::error!("❌ Invalid background job configuration: {:?}", e);
+            std::process::exit(1);
+        }
+    };
+    actix_web::rt::spawn(trash_service::run_purge_job(
+        store.clone(),
+        retention,
+        Duration::from_secs(60 * 60),
+    ));
+    actix_web::rt::spawn(session_service::run_prune_job(store.clone(), Duration::from_secs(60 * 60)));
+    actix_web::rt::spawn(store.scheduler(Arc::new(SystemClock)).run(schedule_every));
+
+    let providers = web::Data::new(Providers::from_env().with_discovered().await);
+    let session_mode = web::Data::new(SessionMode::from_env());
 
     let host = env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
     let port = env::var("PORT")
@@ -46,6 +62,9 @@ async fn main() -> std::io::Result<()> {
     HttpServer::new(move || {
         App::new()
             .wrap(Logger::default())
+            .configure(|cfg| store.configure(cfg))
+            .app_data(providers.clone())
+            .app_data(session_mode.clone())
             .route("/", web::get().to(root))
             .configure(routes::config)
             .default_service(web::route().to(not_found))
@@ -1,6 +1,12 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use surrealdb::sql::Thing;
 
+use crate::models::listing::Filter;
+use crate::models::locale::Localized;
+use crate::models::status::Status;
+use crate::models::{Record, impl_record};
+
 #[derive(Debug, Clone)]
 pub struct RecordIdReturn(pub String);
 
@@ -28,10 +34,18 @@ pub struct About {
     pub id: Option<RecordIdReturn>,
     pub domain: String,
     pub name: String,
-    pub headline: String,
-    pub description: String,
+    pub headline: Localized,
+    pub description: Localized,
     pub location: Location,
     pub interests: Vec<String>,
+    #[serde(default)]
+    pub status: Status,
+    /// When the site was added; set on create
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime<Utc>>,
+    /// Set while the record is in the trash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 
@@ -53,11 +67,18 @@ pub struct Education {
     pub id: Option<RecordIdReturn>,
     pub name: String,
     pub r#type: EducationType,
-    pub degree: Option<String>,
+    pub degree: Option<Localized>,
     pub class: Option<String>,
-    pub specialization: Option<String>,
+    pub specialization: Option<Localized>,
     pub location: Location,
     pub year: YearRange,
+    #[serde(default)]
+    pub position: Option<u32>,
+    #[serde(default)]
+    pub status: Status,
+    /// Set while the record is in the trash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -74,6 +95,11 @@ pub struct Contact {
     pub linkedin: String,
     pub twitter: String,
     pub instagram: String,
+    #[serde(default)]
+    pub status: Status,
+    /// Set while the record is in the trash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -83,9 +109,25 @@ pub struct Certificate {
     pub issuer: Vec<String>,
     pub url: Option<String>,
     pub year: i32,
-    pub description: Option<String>,
+    pub description: Option<Localized>,
     pub image: Option<String>,
     pub tags: Vec<String>,
+    #[serde(default)]
+    pub position: Option<u32>,
+    #[serde(default)]
+    pub status: Status,
+    /// When the certificate was added; set on create
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime<Utc>>,
+    /// When a draft goes live; cleared once the scheduler has published it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publish_at: Option<DateTime<Utc>>,
+    /// When the record goes back to draft; cleared once applied
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unpublish_at: Option<DateTime<Utc>>,
+    /// Set while the record is in the trash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -100,6 +142,13 @@ pub struct ProgLanguage {
     pub id: Option<RecordIdReturn>,
     pub name: String,
     pub level: ProgrammingLevel,
+    #[serde(default)]
+    pub position: Option<u32>,
+    #[serde(default)]
+    pub status: Status,
+    /// Set while the record is in the trash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -115,13 +164,93 @@ pub struct SpokenLanguage {
     pub id: Option<RecordIdReturn>,
     pub name: String,
     pub proficiency: ProficiencyLevel,
+    #[serde(default)]
+    pub position: Option<u32>,
+    #[serde(default)]
+    pub status: Status,
+    /// Set while the record is in the trash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TechStack {
     pub id: Option<RecordIdReturn>,
     pub name: String,
-    pub description: Option<String>,
+    pub description: Option<Localized>,
     pub logo: Option<String>,
     pub tags: Vec<String>,
-}
\ No newline at end of file
+    #[serde(default)]
+    pub position: Option<u32>,
+    #[serde(default)]
+    pub status: Status,
+    /// Set while the record is in the trash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl_record!(About, "about", {
+    /// About records are the registry of sites, shared by all of them.
+    const SITE_SCOPED: bool = false;
+    const LOCALIZED: &'static [&'static str] = &["headline", "description"];
+
+    fn created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
+
+    fn set_created_at(&mut self, at: DateTime<Utc>) {
+        self.created_at = Some(at);
+    }
+});
+impl_record!(Education, "education", orderable, {
+    const LOCALIZED: &'static [&'static str] = &["degree", "specialization"];
+});
+impl_record!(Certificate, "certificates", orderable, {
+    const FILTERS: &'static [(&'static str, Filter)] = &[
+        ("tag", Filter::Contains("tags")),
+        ("year", Filter::Equals("year")),
+    ];
+    const LOCALIZED: &'static [&'static str] = &["description"];
+    const SCHEDULED: bool = true;
+
+    fn created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
+
+    fn set_created_at(&mut self, at: DateTime<Utc>) {
+        self.created_at = Some(at);
+    }
+
+    fn publish_at(&self) -> Option<DateTime<Utc>> {
+        self.publish_at
+    }
+
+    fn unpublish_at(&self) -> Option<DateTime<Utc>> {
+        self.unpublish_at
+    }
+});
+impl_record!(ProgLanguage, "programming_languages", orderable);
+impl_record!(SpokenLanguage, "spoken_languages", orderable);
+impl_record!(TechStack, "tech_stacks", orderable, {
+    const FILTERS: &'static [(&'static str, Filter)] = &[("tag", Filter::Contains("tags"))];
+    const LOCALIZED: &'static [&'static str] = &["description"];
+});
+
+/// Contact is a singleton stored under a fixed record id.
+impl Record for Contact {
+    const TABLE: &'static str = "contact";
+
+    fn id(&self) -> Option<&str> {
+        Some("default")
+    }
+
+    fn set_id(&mut self, _id: String) {}
+
+    fn status(&self) -> Status {
+        self.status
+    }
+
+    fn deleted_at(&self) -> Option<DateTime<Utc>> {
+        self.deleted_at
+    }
+}
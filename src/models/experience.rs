@@ -0,0 +1,84 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::APIError;
+use crate::models::status::Status;
+use crate::models::about::{Location, RecordIdReturn, TechStack};
+use crate::models::impl_record;
+use crate::models::listing::{Filter, SortKey};
+use crate::repository::References;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub enum EmploymentType {
+    FullTime,
+    PartTime,
+    Internship,
+    Freelance,
+    Contract,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct YearMonth {
+    pub year: i32,
+    pub month: u8,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MonthRange {
+    pub from: YearMonth,
+    /// `None` while the position is ongoing
+    pub to: Option<YearMonth>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Experience {
+    pub id: Option<RecordIdReturn>,
+    pub company: String,
+    pub role: String,
+    pub r#type: EmploymentType,
+    pub location: Location,
+    #[serde(default)]
+    pub remote: bool,
+    pub period: MonthRange,
+    #[serde(default)]
+    pub highlights: Vec<String>,
+    /// Ids of live records in `tech_stacks`, checked on every write
+    #[serde(default)]
+    pub tech_stacks: Vec<String>,
+    #[serde(default)]
+    pub status: Status,
+    /// Set while the record is in the trash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl_record!(Experience, "experience", {
+    const FILTERS: &'static [(&'static str, Filter)] = &[
+        ("type", Filter::Equals("type")),
+        ("tech_stack", Filter::Contains("tech_stacks")),
+    ];
+    /// Newest first: ongoing positions, then by end and start month descending.
+    const ORDER: &'static [SortKey<'static>] = &[
+        SortKey::desc("period.to.year").missing_first(),
+        SortKey::desc("period.to.month"),
+        SortKey::desc("period.from.year"),
+        SortKey::desc("period.from.month"),
+    ];
+
+    fn validate(&self) -> Result<(), APIError> {
+        let MonthRange { from, to } = &self.period;
+        for month in std::iter::once(from).chain(to) {
+            if !(1..=12).contains(&month.month) {
+                return Err(APIError::Validation(format!("invalid month {}", month.month)));
+            }
+        }
+        if to.is_some_and(|to| to < *from) {
+            return Err(APIError::Validation("period ends before it starts".to_string()));
+        }
+        Ok(())
+    }
+
+    fn references(&self, references: &mut References) {
+        references.to::<TechStack>(&self.tech_stacks);
+    }
+});
@@ -0,0 +1,299 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::APIError;
+use crate::models::Record;
+use crate::models::status::Status;
+
+pub const DEFAULT_LIMIT: usize = 50;
+pub const MAX_LIMIT: usize = 100;
+
+/// A query parameter a list endpoint can be filtered by, mapped to a model field.
+#[derive(Debug, Clone, Copy)]
+pub enum Filter {
+    /// `?param=value` matches records whose field equals `value`
+    Equals(&'static str),
+    /// `?param=value` matches records whose array field contains `value`
+    Contains(&'static str),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    Asc,
+    Desc,
+}
+
+/// Where records without a sort field go, whatever the [`Order`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Missing {
+    First,
+    Last,
+}
+
+/// One field records are sorted by, see [`Record::ORDER`].
+#[derive(Debug, Clone, Copy)]
+pub struct SortKey<'a> {
+    /// Path of the field, dot separated
+    pub field: &'a str,
+    pub order: Order,
+    /// `None` sorts missing and null values as the smallest ones
+    pub missing: Option<Missing>,
+}
+
+impl<'a> SortKey<'a> {
+    pub const fn asc(field: &'a str) -> Self {
+        Self {
+            field,
+            order: Order::Asc,
+            missing: None,
+        }
+    }
+
+    pub const fn desc(field: &'a str) -> Self {
+        Self {
+            field,
+            order: Order::Desc,
+            missing: None,
+        }
+    }
+
+    pub const fn missing_first(self) -> Self {
+        Self {
+            missing: Some(Missing::First),
+            ..self
+        }
+    }
+
+    pub const fn missing_last(self) -> Self {
+        Self {
+            missing: Some(Missing::Last),
+            ..self
+        }
+    }
+}
+
+/// `?limit=&cursor=&sort=field&order=asc|desc` plus the filters of the model.
+/// `?lang=` is accepted and left to [`Locale`](crate::models::locale::Locale).
+/// Stores run it as a query, see [`Repository::page`](crate::repository::Repository::page).
+/// Trashed records are never listed.
+#[derive(Debug, Clone)]
+pub struct ListQuery {
+    pub limit: usize,
+    /// Offset into the filtered and sorted records to resume from, taken from
+    /// a previous `next_cursor`. Records written between two requests shift
+    /// it, so a page may repeat or skip a record.
+    pub cursor: usize,
+    pub sort: Option<String>,
+    pub order: Order,
+    pub filters: Vec<(&'static str, Filter, String)>,
+    /// Leaves out records that are not [published](Status::Published), for the public
+    pub published_only: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// The `cursor` of the next page, the offset of its first record
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            next_cursor: self.next_cursor,
+        }
+    }
+}
+
+impl Default for ListQuery {
+    fn default() -> Self {
+        Self {
+            limit: DEFAULT_LIMIT,
+            cursor: 0,
+            sort: None,
+            order: Order::Asc,
+            filters: Vec::new(),
+            published_only: false,
+        }
+    }
+}
+
+impl ListQuery {
+    /// Parses query parameters, rejecting filters that `T` does not support.
+    pub fn parse<T: Record>(params: &HashMap<String, String>) -> Result<Self, APIError> {
+        let mut query = Self::default();
+
+        for (key, value) in params {
+            match key.as_str() {
+                "limit" => {
+                    query.limit = value
+                        .parse()
+                        .ok()
+                        .filter(|limit| (1..=MAX_LIMIT).contains(limit))
+                        .ok_or_else(|| {
+                            APIError::Validation(format!("limit must be between 1 and {}", MAX_LIMIT))
+                        })?;
+                }
+                "cursor" => {
+                    query.cursor = value
+                        .parse()
+                        .map_err(|_| APIError::Validation("invalid cursor".to_string()))?;
+                }
+                "sort" => {
+                    let valid = !value.is_empty()
+                        && value
+                            .split('.')
+                            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'));
+                    if !valid {
+                        return Err(APIError::Validation(format!("invalid sort field `{}`", value)));
+                    }
+                    query.sort = Some(value.clone());
+                }
+                "order" => {
+                    query.order = match value.as_str() {
+                        "asc" => Order::Asc,
+                        "desc" => Order::Desc,
+                        _ => return Err(APIError::Validation("order must be asc or desc".to_string())),
+                    };
+                }
+                // Read by the `Locale` extractor
+                "lang" => {}
+                _ => {
+                    let (name, filter) = T::FILTERS
+                        .iter()
+                        .find(|(name, _)| name == key)
+                        .ok_or_else(|| APIError::Validation(format!("unknown query parameter `{}`", key)))?;
+                    query.filters.push((name, *filter, value.clone()));
+                }
+            }
+        }
+        query.filters.sort_by_key(|(name, _, _)| *name);
+
+        Ok(query)
+    }
+
+    /// What records are sorted by: the `sort` field, then the model's
+    /// [`ORDER`](Record::ORDER). Ties keep the order of record ids.
+    pub fn sort_keys<T: Record>(&self) -> Vec<SortKey<'_>> {
+        let sort = self.sort.as_deref().map(|field| SortKey {
+            field,
+            order: self.order,
+            missing: None,
+        });
+        sort.into_iter().chain(T::ORDER.iter().copied()).collect()
+    }
+
+    /// Runs the query in process over a whole table in id order, for stores
+    /// that cannot run it themselves.
+    pub fn apply<T: Record>(&self, items: Vec<T>) -> Result<Page<T>, APIError> {
+        let mut rows = Vec::with_capacity(items.len());
+        for item in items {
+            if item.deleted_at().is_some() || (self.published_only && item.status() != Status::Published) {
+                continue;
+            }
+            let value = serde_json::to_value(&item).map_err(|_| APIError::InternalServerError)?;
+            if self.filters.iter().all(|(_, filter, expected)| matches(&value, *filter, expected)) {
+                rows.push((value, item));
+            }
+        }
+
+        let keys = self.sort_keys::<T>();
+        rows.sort_by(|(a, _), (b, _)| compare_by(a, b, &keys));
+
+        let total = rows.len();
+        let items: Vec<T> = rows
+            .into_iter()
+            .skip(self.cursor)
+            .take(self.limit)
+            .map(|(_, item)| item)
+            .collect();
+
+        let next = self.cursor + items.len();
+        Ok(Page {
+            items,
+            next_cursor: (next < total).then(|| next.to_string()),
+        })
+    }
+}
+
+/// Sorts `items` by `keys`, keeping the current order of ties.
+pub fn sort<T: Record>(items: &mut [T], keys: &[SortKey]) {
+    let mut rows: Vec<(Value, T)> = items
+        .iter()
+        .map(|item| (serde_json::to_value(item).unwrap_or_default(), item.clone()))
+        .collect();
+    rows.sort_by(|(a, _), (b, _)| compare_by(a, b, keys));
+    for (slot, (_, item)) in items.iter_mut().zip(rows) {
+        *slot = item;
+    }
+}
+
+fn compare_by(a: &Value, b: &Value, keys: &[SortKey]) -> Ordering {
+    fn missing(value: Option<&Value>) -> bool {
+        value.is_none_or(Value::is_null)
+    }
+
+    keys.iter()
+        .map(|key| {
+            let pointer = format!("/{}", key.field.replace('.', "/"));
+            let (a, b) = (a.pointer(&pointer), b.pointer(&pointer));
+            let first = match key.missing {
+                Some(Missing::Last) => missing(a).cmp(&missing(b)),
+                Some(Missing::First) => missing(b).cmp(&missing(a)),
+                None => Ordering::Equal,
+            };
+            first.then_with(|| match key.order {
+                Order::Asc => compare(a, b),
+                Order::Desc => compare(a, b).reverse(),
+            })
+        })
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Whether a JSON scalar reads as `expected` in a query string.
+pub(crate) fn scalar_eq(value: &Value, expected: &str) -> bool {
+    match value {
+        Value::String(s) => s == expected,
+        Value::Number(n) => n.to_string() == expected,
+        Value::Bool(b) => b.to_string() == expected,
+        _ => false,
+    }
+}
+
+fn matches(record: &Value, filter: Filter, expected: &str) -> bool {
+    match filter {
+        Filter::Equals(field) => record.get(field).is_some_and(|value| scalar_eq(value, expected)),
+        Filter::Contains(field) => record
+            .get(field)
+            .and_then(Value::as_array)
+            .is_some_and(|values| values.iter().any(|value| scalar_eq(value, expected))),
+    }
+}
+
+/// Missing and null values sort first, then booleans, numbers and strings.
+fn compare(a: Option<&Value>, b: Option<&Value>) -> Ordering {
+    fn rank(value: Option<&Value>) -> u8 {
+        match value {
+            None | Some(Value::Null) => 0,
+            Some(Value::Bool(_)) => 1,
+            Some(Value::Number(_)) => 2,
+            Some(Value::String(_)) => 3,
+            Some(_) => 4,
+        }
+    }
+
+    match (a, b) {
+        (Some(Value::Bool(a)), Some(Value::Bool(b))) => a.cmp(b),
+        (Some(Value::Number(a)), Some(Value::Number(b))) => a
+            .as_f64()
+            .partial_cmp(&b.as_f64())
+            .unwrap_or(Ordering::Equal),
+        (Some(Value::String(a)), Some(Value::String(b))) => a.cmp(b),
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::BTreeMap;
+use std::env;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::APIError;
+use crate::models::{Record, document};
+
+/// `DEFAULT_LOCALE`: the locale responses fall back to, `en` unless set.
+pub fn default_locale() -> String {
+    env::var("DEFAULT_LOCALE").unwrap_or_else(|_| "en".to_string())
+}
+
+/// `LOCALES`: the comma-separated locales content is translated into,
+/// `en,ta` unless set. Always includes the [`default_locale`].
+pub fn locales() -> Vec<String> {
+    let mut locales: Vec<String> = env::var("LOCALES")
+        .unwrap_or_else(|_| "en,ta".to_string())
+        .split(',')
+        .map(|locale| locale.trim().to_ascii_lowercase())
+        .filter(|locale| !locale.is_empty())
+        .collect();
+    let default = default_locale();
+    if !locales.contains(&default) {
+        locales.insert(0, default);
+    }
+    locales
+}
+
+/// Text with a translation per locale, stored as `{"en": "...", "ta": "..."}`.
+/// A plain string is read as the text of the [`default_locale`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "Text")]
+pub struct Localized(pub BTreeMap<String, String>);
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Text {
+    Plain(String),
+    Translated(BTreeMap<String, String>),
+}
+
+impl From<Text> for Localized {
+    fn from(text: Text) -> Self {
+        match text {
+            Text::Plain(text) => Localized::new(text),
+            Text::Translated(translations) => Localized(translations),
+        }
+    }
+}
+
+impl Localized {
+    /// Text in the [`default_locale`] only.
+    pub fn new(text: impl Into<String>) -> Self {
+        Localized(BTreeMap::from([(default_locale(), text.into())]))
+    }
+
+    /// The text in `locale`, falling back to the [`default_locale`] and then
+    /// to any translation there is.
+    pub fn resolve(&self, locale: &str) -> &str {
+        self.0
+            .get(locale)
+            .or_else(|| self.0.get(&default_locale()))
+            .or_else(|| self.0.values().next())
+            .map(String::as_str)
+            .unwrap_or_default()
+    }
+
+    /// The [`locales`] without a non-empty translation.
+    pub fn missing(&self) -> Vec<String> {
+        locales()
+            .into_iter()
+            .filter(|locale| self.0.get(locale).is_none_or(|text| text.trim().is_empty()))
+            .collect()
+    }
+}
+
+/// The language a response is written in, negotiated from `?lang=` or
+/// `Accept-Language`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Locale {
+    /// One of the [`locales`]: translatable fields are plain strings
+    One(String),
+    /// `?lang=all`: translatable fields keep every translation, for editing
+    All,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale::One(default_locale())
+    }
+}
+
+impl Locale {
+    /// `?lang=`, which must name one of the [`locales`] or be `all`.
+    pub fn parse(lang: &str) -> Result<Self, APIError> {
+        let lang = lang.trim().to_ascii_lowercase();
+        if lang == "all" {
+            return Ok(Locale::All);
+        }
+        if locales().contains(&lang) {
+            Ok(Locale::One(lang))
+        } else {
+            Err(APIError::Validation(format!("unsupported lang `{}`", lang)))
+        }
+    }
+
+    /// The first of `preferred` language tags, best first, that content is
+    /// translated into, or the [`default_locale`].
+    pub fn negotiate<'a>(preferred: impl IntoIterator<Item = &'a str>) -> Self {
+        let locales = locales();
+        preferred
+            .into_iter()
+            .filter_map(|tag| tag.split(['-', '_']).next())
+            .map(str::to_ascii_lowercase)
+            .find(|language| locales.contains(language))
+            .map(Locale::One)
+            .unwrap_or_default()
+    }
+
+    /// `record` as JSON with its [`LOCALIZED`](Record::LOCALIZED) fields
+    /// resolved to this locale.
+    pub fn record<T: Record>(&self, record: &T) -> Value {
+        let mut value = serde_json::to_value(record).unwrap_or_default();
+        if let Locale::One(locale) = self {
+            for field in T::LOCALIZED {
+                if let Some(text) = value.get_mut(*field)
+                    && text.is_object()
+                    && let Ok(translations) = serde_json::from_value::<Localized>(text.take())
+                {
+                    *text = Value::String(translations.resolve(locale).to_string());
+                }
+            }
+        }
+        value
+    }
+}
+
+/// The translations of `record`'s [`LOCALIZED`](Record::LOCALIZED) fields
+/// that have text.
+pub fn translations<T: Record>(record: &T) -> Result<Vec<(&'static str, Localized)>, APIError> {
+    let document = document(record)?;
+    let mut fields = Vec::new();
+    for field in T::LOCALIZED {
+        if let Some(text) = document.get(*field).filter(|text| !text.is_null()) {
+            let text = serde_json::from_value(text.clone()).map_err(|_| APIError::InternalServerError)?;
+            fields.push((*field, text));
+        }
+    }
+    Ok(fields)
+}
+
+/// Rejects translations into locales that are not in [`locales`].
+pub fn check<T: Record>(record: &T) -> Result<(), APIError> {
+    let locales = locales();
+    for (field, text) in translations(record)? {
+        if let Some(locale) = text.0.keys().find(|locale| !locales.contains(locale)) {
+            return Err(APIError::Validation(format!("unsupported locale `{}` in `{}`", locale, field)));
+        }
+    }
+    Ok(())
+}
+
+/// Rewrites plain strings in a merge patch of [`LOCALIZED`](Record::LOCALIZED)
+/// fields to the text of the [`default_locale`], so that the other
+/// translations are kept.
+pub fn patch<T: Record>(mut patch: Value) -> Value {
+    for field in T::LOCALIZED {
+        if let Some(text) = patch.get_mut(*field)
+            && let Some(plain) = text.as_str()
+        {
+            *text = serde_json::to_value(Localized::new(plain)).unwrap_or_default();
+        }
+    }
+    patch
+}
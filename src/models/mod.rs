@@ -1 +1,179 @@
 pub mod about;
+pub mod experience;
+pub mod listing;
+pub mod locale;
+pub mod post;
+pub mod project;
+pub mod revision;
+pub mod session;
+pub mod site;
+pub mod status;
+
+use chrono::{DateTime, Utc};
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+use crate::error::APIError;
+use crate::models::listing::{Filter, SortKey};
+use crate::models::status::Status;
+use crate::repository::References;
+use crate::utils::{etag, merge_patch};
+
+/// A model that is persisted as a record in a table.
+pub trait Record: Serialize + DeserializeOwned + Clone + Send + Sync + 'static {
+    const TABLE: &'static str;
+    /// Query parameters list endpoints can be filtered by
+    const FILTERS: &'static [(&'static str, Filter)] = &[];
+    /// Whether each [`Site`](site::Site) has its own copy of the table
+    const SITE_SCOPED: bool = true;
+    /// [`Localized`](locale::Localized) fields, which responses resolve to one locale
+    const LOCALIZED: &'static [&'static str] = &[];
+    /// Whether the model has a `publish_at`/`unpublish_at` schedule
+    const SCHEDULED: bool = false;
+    /// Whether reads may be served from a [`ReadCache`](crate::repository::ReadCache)
+    const CACHED: bool = true;
+    /// The default order of a listing: by position, unpositioned records
+    /// last, otherwise by id. Declared so that stores can sort by it.
+    const ORDER: &'static [SortKey<'static>] = &[SortKey::asc("position").missing_last()];
+
+    fn id(&self) -> Option<&str>;
+    fn set_id(&mut self, id: String);
+
+    /// Fills in fields derived from the others, e.g. a post's reading time,
+    /// before the record is validated and written.
+    fn prepare(&mut self) {}
+
+    /// Checks invariants that serde cannot express, before the record is written.
+    fn validate(&self) -> Result<(), APIError> {
+        Ok(())
+    }
+
+    /// Declares the records of other models this one points at, which must
+    /// exist when it is written.
+    fn references(&self, _references: &mut References) {}
+
+    /// Manual display position, for models that can be reordered.
+    fn position(&self) -> Option<u32> {
+        None
+    }
+
+    fn set_position(&mut self, _position: u32) {}
+
+    /// Publication state; models without one are always published.
+    fn status(&self) -> Status {
+        Status::Published
+    }
+
+    /// When the record was first written, for models that keep track of it.
+    fn created_at(&self) -> Option<DateTime<Utc>> {
+        None
+    }
+
+    fn set_created_at(&mut self, _at: DateTime<Utc>) {}
+
+    /// When the scheduler should publish the record, for models that can be scheduled.
+    fn publish_at(&self) -> Option<DateTime<Utc>> {
+        None
+    }
+
+    /// When the scheduler should take the record back to draft.
+    fn unpublish_at(&self) -> Option<DateTime<Utc>> {
+        None
+    }
+
+    /// What a draft that goes live at `at` gets besides its status, as a
+    /// merge patch, e.g. a post's publication date when it has none yet.
+    fn on_publish(&self, _at: DateTime<Utc>) -> Value {
+        Value::Object(Default::default())
+    }
+
+    /// When the record was moved to the trash. Trashed records are hidden
+    /// from reads until they are restored or purged.
+    fn deleted_at(&self) -> Option<DateTime<Utc>> {
+        None
+    }
+
+    /// Puts a listing in its default [order](Self::ORDER), keeping the
+    /// current order of ties.
+    fn sort(items: &mut [Self]) {
+        listing::sort(items, Self::ORDER);
+    }
+}
+
+/// A hash of the record's content, exposed as its `ETag`.
+pub fn version<T: Record>(record: &T) -> String {
+    etag(&serde_json::to_vec(record).unwrap_or_default())
+}
+
+/// The record as JSON without its `id`, which `RecordIdReturn` can only
+/// deserialize from a SurrealDB `Thing`.
+pub fn document<T: Record>(record: &T) -> Result<Value, APIError> {
+    let mut document = serde_json::to_value(record).map_err(|_| APIError::InternalServerError)?;
+    if let Value::Object(fields) = &mut document {
+        fields.remove("id");
+    }
+    Ok(document)
+}
+
+/// The inverse of [`document`]: reads a record back and gives it `id`.
+pub fn from_document<T: Record>(document: Value, id: &str) -> Result<T, APIError> {
+    let mut record: T = serde_json::from_value(document).map_err(|e| APIError::Validation(e.to_string()))?;
+    record.set_id(id.to_string());
+    Ok(record)
+}
+
+/// Applies a JSON Merge Patch to a copy of `record`, keeping its id.
+pub fn patched<T: Record>(record: &T, patch: &Value) -> Result<T, APIError> {
+    let mut document = document(record)?;
+    merge_patch(&mut document, patch);
+
+    match record.id() {
+        Some(id) => from_document(document, id),
+        None => serde_json::from_value(document).map_err(|e| APIError::Validation(e.to_string())),
+    }
+}
+
+/// Implements [`Record`] for a model with `id: Option<RecordIdReturn>`,
+/// `status: Status` and `deleted_at: Option<DateTime<Utc>>` fields. `orderable` models also have a
+/// `position: Option<u32>` field. Overrides for
+/// the provided methods can be passed in a trailing block.
+macro_rules! impl_record {
+    ($model:ty, $table:literal, orderable $(, { $($extra:tt)* })?) => {
+        impl_record!($model, $table, {
+            fn position(&self) -> Option<u32> {
+                self.position
+            }
+
+            fn set_position(&mut self, position: u32) {
+                self.position = Some(position);
+            }
+
+            $($($extra)*)?
+        });
+    };
+    ($model:ty, $table:literal $(, { $($extra:tt)* })?) => {
+        impl $crate::models::Record for $model {
+            const TABLE: &'static str = $table;
+
+            fn id(&self) -> Option<&str> {
+                self.id.as_ref().map(|id| id.0.as_str())
+            }
+
+            fn set_id(&mut self, id: String) {
+                self.id = Some($crate::models::about::RecordIdReturn(id));
+            }
+
+            fn status(&self) -> $crate::models::status::Status {
+                self.status
+            }
+
+            fn deleted_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+                self.deleted_at
+            }
+
+            $($($extra)*)?
+        }
+    };
+}
+
+pub(crate) use impl_record;
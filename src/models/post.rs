@@ -0,0 +1,112 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::error::APIError;
+use crate::models::Record;
+use crate::models::listing::{Filter, SortKey};
+use crate::models::status::Status;
+use crate::utils::{is_valid_slug, reading_time};
+
+/// A blog post. The slug doubles as the record id.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Post {
+    pub slug: String,
+    pub title: String,
+    /// Markdown
+    pub body: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// When the post went live: given by the admin, or set when it is first published
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub published_at: Option<DateTime<Utc>>,
+    /// Minutes, computed from `body` whenever the post is written
+    #[serde(default)]
+    pub reading_time: u32,
+    #[serde(default)]
+    pub status: Status,
+    /// When a draft goes live; cleared once the scheduler has published it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publish_at: Option<DateTime<Utc>>,
+    /// When the record goes back to draft; cleared once applied
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unpublish_at: Option<DateTime<Utc>>,
+    /// Set while the record is in the trash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl Record for Post {
+    const TABLE: &'static str = "posts";
+    const FILTERS: &'static [(&'static str, Filter)] = &[("tag", Filter::Contains("tags"))];
+    const SCHEDULED: bool = true;
+    /// Newest first
+    const ORDER: &'static [SortKey<'static>] = &[SortKey::desc("published_at")];
+
+    fn id(&self) -> Option<&str> {
+        Some(&self.slug)
+    }
+
+    fn set_id(&mut self, id: String) {
+        self.slug = id;
+    }
+
+    fn status(&self) -> Status {
+        self.status
+    }
+
+    fn deleted_at(&self) -> Option<DateTime<Utc>> {
+        self.deleted_at
+    }
+
+    fn publish_at(&self) -> Option<DateTime<Utc>> {
+        self.publish_at
+    }
+
+    fn unpublish_at(&self) -> Option<DateTime<Utc>> {
+        self.unpublish_at
+    }
+
+    fn on_publish(&self, at: DateTime<Utc>) -> Value {
+        match self.published_at {
+            Some(_) => json!({}),
+            None => json!({ "published_at": at }),
+        }
+    }
+
+    fn prepare(&mut self) {
+        self.reading_time = reading_time(&self.body);
+        if self.status == Status::Published && self.published_at.is_none() {
+            self.published_at = Some(Utc::now());
+        }
+    }
+
+    fn validate(&self) -> Result<(), APIError> {
+        if !is_valid_slug(&self.slug) {
+            return Err(APIError::Validation(format!("invalid slug `{}`", self.slug)));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PostSummary {
+    pub slug: String,
+    pub title: String,
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published_at: Option<DateTime<Utc>>,
+    pub reading_time: u32,
+}
+
+impl From<Post> for PostSummary {
+    fn from(post: Post) -> Self {
+        Self {
+            slug: post.slug,
+            title: post.title,
+            tags: post.tags,
+            published_at: post.published_at,
+            reading_time: post.reading_time,
+        }
+    }
+}
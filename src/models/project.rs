@@ -0,0 +1,94 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::APIError;
+use crate::models::Record;
+use crate::models::listing::Filter;
+use crate::models::status::Status;
+use crate::models::about::{TechStack, YearRange};
+use crate::repository::References;
+use crate::utils::is_valid_slug;
+
+/// A portfolio project. The slug doubles as the record id.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Project {
+    pub slug: String,
+    pub title: String,
+    pub summary: String,
+    /// Markdown
+    pub body: String,
+    pub repo_url: Option<String>,
+    pub live_url: Option<String>,
+    /// Ids of live records in `tech_stacks`, checked on every write
+    #[serde(default)]
+    pub tech_stacks: Vec<String>,
+    pub cover_image: Option<String>,
+    #[serde(default)]
+    pub featured: bool,
+    pub year: YearRange,
+    #[serde(default)]
+    pub position: Option<u32>,
+    #[serde(default)]
+    pub status: Status,
+    /// When a draft goes live; cleared once the scheduler has published it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publish_at: Option<DateTime<Utc>>,
+    /// When the record goes back to draft; cleared once applied
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unpublish_at: Option<DateTime<Utc>>,
+    /// Set while the record is in the trash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl Record for Project {
+    const TABLE: &'static str = "projects";
+    const FILTERS: &'static [(&'static str, Filter)] = &[
+        ("featured", Filter::Equals("featured")),
+        ("tech_stack", Filter::Contains("tech_stacks")),
+    ];
+    const SCHEDULED: bool = true;
+
+    fn id(&self) -> Option<&str> {
+        Some(&self.slug)
+    }
+
+    fn set_id(&mut self, id: String) {
+        self.slug = id;
+    }
+
+    fn status(&self) -> Status {
+        self.status
+    }
+
+    fn deleted_at(&self) -> Option<DateTime<Utc>> {
+        self.deleted_at
+    }
+
+    fn publish_at(&self) -> Option<DateTime<Utc>> {
+        self.publish_at
+    }
+
+    fn unpublish_at(&self) -> Option<DateTime<Utc>> {
+        self.unpublish_at
+    }
+
+    fn position(&self) -> Option<u32> {
+        self.position
+    }
+
+    fn set_position(&mut self, position: u32) {
+        self.position = Some(position);
+    }
+
+    fn validate(&self) -> Result<(), APIError> {
+        if !is_valid_slug(&self.slug) {
+            return Err(APIError::Validation(format!("invalid slug `{}`", self.slug)));
+        }
+        Ok(())
+    }
+
+    fn references(&self, references: &mut References) {
+        references.to::<TechStack>(&self.tech_stacks);
+    }
+}
@@ -0,0 +1,55 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::models::about::RecordIdReturn;
+use crate::models::Record;
+use crate::models::listing::SortKey;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Create,
+    Update,
+    Delete,
+    Restore,
+    Publish,
+    Unpublish,
+}
+
+/// One write to a record, kept so it can be inspected and rolled back.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Revision {
+    pub id: Option<RecordIdReturn>,
+    /// Table of the record that was written
+    pub collection: String,
+    pub record: String,
+    pub action: Action,
+    /// Email of the admin who made the write
+    pub author: String,
+    pub created_at: DateTime<Utc>,
+    /// The record as it was before the write, without its id; `None` when it did not exist
+    pub document: Option<Value>,
+}
+
+impl Record for Revision {
+    const TABLE: &'static str = "revisions";
+    /// Newest first
+    const ORDER: &'static [SortKey<'static>] = &[SortKey::desc("created_at")];
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_ref().map(|id| id.0.as_str())
+    }
+
+    fn set_id(&mut self, id: String) {
+        self.id = Some(RecordIdReturn(id));
+    }
+
+    fn created_at(&self) -> Option<DateTime<Utc>> {
+        Some(self.created_at)
+    }
+
+    fn set_created_at(&mut self, at: DateTime<Utc>) {
+        self.created_at = at;
+    }
+}
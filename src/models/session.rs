@@ -0,0 +1,66 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::about::RecordIdReturn;
+use crate::models::Record;
+
+/// A login, kept alive by rotating its refresh token until it expires or is
+/// revoked. Only the hash of the current refresh token is stored.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Session {
+    pub id: Option<RecordIdReturn>,
+    pub email: String,
+    pub name: String,
+    pub picture: String,
+    /// Hex SHA-256 of the secret part of the current refresh token
+    pub refresh_hash: String,
+    /// Hash of the refresh token the current one replaced. Presenting it
+    /// again means it leaked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_hash: Option<String>,
+    /// `jti` of the access token issued with the current refresh token
+    pub access_jti: String,
+    pub access_expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    /// When the refresh token stops working; a refresh does not extend it
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Record for Session {
+    const TABLE: &'static str = "sessions";
+    /// Admins log in once for every site.
+    const SITE_SCOPED: bool = false;
+    /// A cached copy would let a rotated refresh token be used again.
+    const CACHED: bool = false;
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_ref().map(|id| id.0.as_str())
+    }
+
+    fn set_id(&mut self, id: String) {
+        self.id = Some(RecordIdReturn(id));
+    }
+}
+
+/// An access token that must not be accepted anymore, stored under its
+/// `jti` until it would have expired anyway.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RevokedToken {
+    pub id: Option<RecordIdReturn>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Record for RevokedToken {
+    const TABLE: &'static str = "revoked_tokens";
+    const SITE_SCOPED: bool = false;
+    /// A revocation must take effect on the next request.
+    const CACHED: bool = false;
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_ref().map(|id| id.0.as_str())
+    }
+
+    fn set_id(&mut self, id: String) {
+        self.id = Some(RecordIdReturn(id));
+    }
+}
@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::Record;
+use crate::models::about::RecordIdReturn;
+use crate::models::revision::Revision;
+
+/// A site served by this backend, identified by the `domain` of its About
+/// record. Collections are stored per site: the default site (`DEFAULT_SITE`,
+/// or the [`DefaultSite`] stored when there first was a site) uses the plain table names and
+/// every other site its own copies of them, suffixed with the id of its About
+/// record. About records themselves are shared.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Site {
+    /// The domain the site was resolved from, when there is one
+    pub domain: Option<String>,
+    /// Whether the site uses the plain table names
+    pub default: bool,
+    /// The id of the site's About record, unless it is the default site
+    #[serde(rename = "about", skip_serializing_if = "Option::is_none")]
+    key: Option<String>,
+}
+
+impl Site {
+    pub fn default_for(domain: Option<String>) -> Self {
+        Self {
+            domain,
+            default: true,
+            key: None,
+        }
+    }
+
+    /// A non-default site whose About record has the id `key`.
+    pub fn scoped(domain: String, key: String) -> Self {
+        Self {
+            domain: Some(domain),
+            default: false,
+            key: Some(key),
+        }
+    }
+
+    /// The table this site keeps `T` records in.
+    pub fn table<T: Record>(&self) -> String {
+        match &self.key {
+            Some(key) if T::SITE_SCOPED => format!("{}__{}", T::TABLE, key),
+            _ => T::TABLE.to_string(),
+        }
+    }
+
+    /// The revisions table that keeps the history of `T` records. Models that
+    /// are not [`SITE_SCOPED`](Record::SITE_SCOPED) have one history, in the
+    /// default site's table, whichever site they are written through.
+    pub fn revisions_table<T: Record>(&self) -> String {
+        if T::SITE_SCOPED {
+            self.table::<Revision>()
+        } else {
+            Revision::TABLE.to_string()
+        }
+    }
+}
+
+/// The domain that uses the plain table names when `DEFAULT_SITE` is unset,
+/// stored under the id `default` once there is a site. It never changes
+/// afterwards, so trashing, purging or adding About records cannot hand a
+/// site's tables to another one.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DefaultSite {
+    pub id: Option<RecordIdReturn>,
+    pub domain: String,
+}
+
+impl Record for DefaultSite {
+    const TABLE: &'static str = "default_site";
+    const SITE_SCOPED: bool = false;
+    /// Another instance may store it first; every instance must see that one.
+    const CACHED: bool = false;
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_ref().map(|id| id.0.as_str())
+    }
+
+    fn set_id(&mut self, id: String) {
+        self.id = Some(RecordIdReturn(id));
+    }
+}
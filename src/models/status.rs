@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::Record;
+
+/// Publication state of a record. Records written before statuses existed
+/// have none stored and count as published; new records sent without one
+/// are drafts (see [`RecordBody`](crate::routes::record_body::RecordBody)), and
+/// replacements sent without one keep the current status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Draft,
+    #[default]
+    Published,
+    Archived,
+}
+
+/// Who a read is for: the public only sees published records, admins see all of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Viewer {
+    Public,
+    Admin,
+    /// Holder of a preview token, which is only ever checked against the
+    /// one record it was minted for
+    Preview,
+}
+
+impl Viewer {
+    pub fn can_see<T: Record>(self, record: &T) -> bool {
+        self != Viewer::Public || record.status() == Status::Published
+    }
+}
@@ -0,0 +1,256 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::error::APIError;
+use crate::models::Record;
+use crate::models::listing::{ListQuery, Page};
+use crate::models::revision::Revision;
+use crate::repository::{Repository, Stamp, Tables, Write};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Key {
+    List(String),
+    /// A page of a table, keyed by its query
+    Page(String, String),
+    Record(String, String),
+}
+
+impl Key {
+    fn table(&self) -> &str {
+        match self {
+            Key::List(table) | Key::Page(table, _) | Key::Record(table, _) => table,
+        }
+    }
+}
+
+struct Entry {
+    value: Arc<dyn Any + Send + Sync>,
+    expires: Instant,
+}
+
+#[derive(Default)]
+struct Entries {
+    values: HashMap<Key, Entry>,
+    /// Bumped on every invalidation of a table, so a read that started
+    /// before a write cannot cache what it saw after the write
+    generations: HashMap<String, u64>,
+}
+
+/// An in-process cache of table listings and single records, shared by every
+/// repository created from the same store. Entries expire after `ttl`; when
+/// `max_entries` is reached, expired entries and then the oldest ones are dropped.
+#[derive(Clone)]
+pub struct ReadCache {
+    entries: Arc<Mutex<Entries>>,
+    ttl: Duration,
+    max_entries: usize,
+}
+
+impl ReadCache {
+    pub fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            entries: Arc::default(),
+            ttl,
+            max_entries,
+        }
+    }
+
+    fn get<V: Clone + Send + Sync + 'static>(&self, key: &Key) -> Option<V> {
+        let mut entries = self.entries.lock().ok()?;
+        match entries.values.get(key) {
+            Some(entry) if entry.expires > Instant::now() => entry.value.downcast_ref::<V>().cloned(),
+            Some(_) => {
+                entries.values.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// The current generation of `table`; pass it to [`insert`](Self::insert)
+    /// with the value read after calling this.
+    fn generation(&self, table: &str) -> u64 {
+        self.entries
+            .lock()
+            .map(|entries| entries.generations.get(table).copied().unwrap_or_default())
+            .unwrap_or_default()
+    }
+
+    /// Caches `value` unless the table was invalidated since `generation`.
+    fn insert<V: Send + Sync + 'static>(&self, key: Key, value: V, generation: u64) {
+        let Ok(mut entries) = self.entries.lock() else {
+            return;
+        };
+        if entries.generations.get(key.table()).copied().unwrap_or_default() != generation {
+            return;
+        }
+
+        let entries = &mut entries.values;
+        if entries.len() >= self.max_entries {
+            let now = Instant::now();
+            entries.retain(|_, entry| entry.expires > now);
+        }
+        while entries.len() >= self.max_entries {
+            let Some(oldest) = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.expires)
+                .map(|(key, _)| key.clone())
+            else {
+                return;
+            };
+            entries.remove(&oldest);
+        }
+
+        entries.insert(
+            key,
+            Entry {
+                value: Arc::new(value),
+                expires: Instant::now() + self.ttl,
+            },
+        );
+    }
+
+    fn invalidate(&self, table: &str, id: Option<&str>) {
+        if let Ok(mut entries) = self.entries.lock() {
+            *entries.generations.entry(table.to_string()).or_default() += 1;
+            let entries = &mut entries.values;
+            entries.remove(&Key::List(table.to_string()));
+            entries.retain(|key, _| !matches!(key, Key::Page(page, _) if page == table));
+            match id {
+                Some(id) => {
+                    entries.remove(&Key::Record(table.to_string(), id.to_string()));
+                }
+                None => entries.retain(|key, _| key.table() != table),
+            }
+        }
+    }
+}
+
+/// Read-through caching in front of another repository. Successful writes
+/// invalidate the table listing, its pages and the written record.
+pub struct CachedRepository<T> {
+    inner: Arc<dyn Repository<T>>,
+    cache: ReadCache,
+}
+
+impl<T> CachedRepository<T> {
+    pub fn new(inner: Arc<dyn Repository<T>>, cache: ReadCache) -> Self {
+        Self { inner, cache }
+    }
+}
+
+#[async_trait]
+impl<T: Record> Repository<T> for CachedRepository<T> {
+    fn table(&self) -> &str {
+        self.inner.table()
+    }
+
+    fn tables(&self) -> &Tables {
+        self.inner.tables()
+    }
+
+    async fn list(&self) -> Result<Vec<T>, APIError> {
+        let key = Key::List(self.table().to_string());
+        if let Some(items) = self.cache.get::<Vec<T>>(&key) {
+            return Ok(items);
+        }
+
+        let generation = self.cache.generation(self.table());
+        let items = self.inner.list().await?;
+        self.cache.insert(key, items.clone(), generation);
+        Ok(items)
+    }
+
+    async fn page(&self, query: &ListQuery) -> Result<Page<T>, APIError> {
+        let key = Key::Page(self.table().to_string(), format!("{:?}", query));
+        if let Some(page) = self.cache.get::<Page<T>>(&key) {
+            return Ok(page);
+        }
+
+        let generation = self.cache.generation(self.table());
+        let page = self.inner.page(query).await?;
+        self.cache.insert(key, page.clone(), generation);
+        Ok(page)
+    }
+
+    async fn find(&self, fields: &[(&'static str, &str)]) -> Result<Vec<T>, APIError> {
+        self.inner.find(fields).await
+    }
+
+    async fn last_created(&self, fields: &[(&'static str, &str)]) -> Result<Option<DateTime<Utc>>, APIError> {
+        self.inner.last_created(fields).await
+    }
+
+    async fn get(&self, id: &str) -> Result<Option<T>, APIError> {
+        let key = Key::Record(self.table().to_string(), id.to_string());
+        if let Some(item) = self.cache.get::<Option<T>>(&key) {
+            return Ok(item);
+        }
+
+        let generation = self.cache.generation(self.table());
+        let item = self.inner.get(id).await?;
+        self.cache.insert(key, item.clone(), generation);
+        Ok(item)
+    }
+
+    async fn get_stamped(&self, id: &str) -> Result<Option<(T, Stamp)>, APIError> {
+        self.inner.get_stamped(id).await
+    }
+
+    async fn create(&self, data: T) -> Result<T, APIError> {
+        let created = self.inner.create(data).await?;
+        self.cache.invalidate(self.table(), created.id());
+        Ok(created)
+    }
+
+    async fn update(&self, id: &str, data: T) -> Result<Option<T>, APIError> {
+        let updated = self.inner.update(id, data).await?;
+        self.cache.invalidate(self.table(), Some(id));
+        Ok(updated)
+    }
+
+    async fn update_if(&self, id: &str, field: &'static str, expected: &str, data: T) -> Result<Option<T>, APIError> {
+        let updated = self.inner.update_if(id, field, expected, data).await?;
+        self.cache.invalidate(self.table(), Some(id));
+        Ok(updated)
+    }
+
+    async fn merge(&self, id: &str, patch: Value) -> Result<Option<T>, APIError> {
+        let merged = self.inner.merge(id, patch).await?;
+        self.cache.invalidate(self.table(), Some(id));
+        Ok(merged)
+    }
+
+    async fn delete(&self, id: &str) -> Result<Option<T>, APIError> {
+        let deleted = self.inner.delete(id).await?;
+        self.cache.invalidate(self.table(), Some(id));
+        Ok(deleted)
+    }
+
+    async fn drop_table(&self) -> Result<(), APIError> {
+        self.inner.drop_table().await?;
+        self.cache.invalidate(self.table(), None);
+        Ok(())
+    }
+
+    async fn reorder(&self, ids: &[String]) -> Result<(), APIError> {
+        self.inner.reorder(ids).await?;
+        self.cache.invalidate(self.table(), None);
+        Ok(())
+    }
+
+    /// Also invalidates when the stamp did not match, as the cached copy is stale.
+    async fn commit(&self, write: Write<T>, revision: Revision, expected: Option<&Stamp>) -> Result<Option<T>, APIError> {
+        let id = write.id().map(str::to_string);
+        let written = self.inner.commit(write, revision, expected).await;
+        self.cache.invalidate(self.table(), id.as_deref());
+        self.cache.invalidate(&self.tables().site().revisions_table::<T>(), None);
+        written
+    }
+}
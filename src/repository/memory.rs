@@ -0,0 +1,228 @@
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, RwLock};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::error::APIError;
+use crate::models::revision::Revision;
+use crate::models::{Record, patched, version};
+use crate::repository::{Repository, Stamp, Tables, Write};
+use crate::models::listing::{ListQuery, Page, scalar_eq};
+
+type Table<T> = Arc<RwLock<BTreeMap<String, T>>>;
+
+/// Process-local tables, shared between every repository created from the same store.
+#[derive(Clone, Default)]
+pub struct MemoryStore {
+    tables: Arc<Mutex<HashMap<String, Arc<dyn Any + Send + Sync>>>>,
+}
+
+impl MemoryStore {
+    pub fn repository<T: Record>(&self, site: Tables) -> MemoryRepository<T> {
+        let name = site.site().table::<T>();
+        MemoryRepository {
+            table: self.table(&name),
+            name,
+            site,
+            store: self.clone(),
+        }
+    }
+
+    fn table<T: Record>(&self, name: &str) -> Table<T> {
+        let mut tables = self.tables.lock().expect("memory store poisoned");
+        tables
+            .entry(name.to_string())
+            .or_insert_with(|| Arc::new(RwLock::new(BTreeMap::<String, T>::new())))
+            .clone()
+            .downcast::<RwLock<BTreeMap<String, T>>>()
+            .unwrap_or_else(|_| panic!("table `{}` is used by more than one model", name))
+    }
+}
+
+pub struct MemoryRepository<T> {
+    name: String,
+    table: Table<T>,
+    site: Tables,
+    store: MemoryStore,
+}
+
+impl<T> MemoryRepository<T> {
+    fn read(&self) -> Result<std::sync::RwLockReadGuard<'_, BTreeMap<String, T>>, APIError> {
+        self.table.read().map_err(|_| APIError::InternalServerError)
+    }
+
+    fn write(&self) -> Result<std::sync::RwLockWriteGuard<'_, BTreeMap<String, T>>, APIError> {
+        self.table.write().map_err(|_| APIError::InternalServerError)
+    }
+}
+
+/// The id `data` gets in the table `name`: its own, or a new one. Fails
+/// with a conflict when a record already has it.
+fn vacant_id<T: Record>(name: &str, table: &BTreeMap<String, T>, data: &T) -> Result<String, APIError> {
+    let id = data
+        .id()
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().simple().to_string());
+
+    if table.contains_key(&id) {
+        return Err(APIError::Conflict(format!(
+            "record `{}:{}` already exists",
+            name,
+            id
+        )));
+    }
+    Ok(id)
+}
+
+fn insert<T: Record>(name: &str, table: &mut BTreeMap<String, T>, mut data: T) -> Result<T, APIError> {
+    let id = vacant_id(name, table, &data)?;
+    data.set_id(id.clone());
+    table.insert(id, data.clone());
+    Ok(data)
+}
+
+/// Process-local records are stamped with their [`version`].
+fn stamp<T: Record>(record: &T) -> Stamp {
+    Stamp(version(record))
+}
+
+fn replace<T: Record>(table: &mut BTreeMap<String, T>, id: &str, mut data: T) -> Option<T> {
+    let existing = table.get_mut(id)?;
+    data.set_id(id.to_string());
+    *existing = data.clone();
+    Some(data)
+}
+
+fn merge<T: Record>(table: &mut BTreeMap<String, T>, id: &str, patch: &Value) -> Result<Option<T>, APIError> {
+    match table.get_mut(id) {
+        Some(existing) => {
+            *existing = patched(existing, patch)?;
+            Ok(Some(existing.clone()))
+        }
+        None => Ok(None),
+    }
+}
+
+#[async_trait]
+impl<T: Record> Repository<T> for MemoryRepository<T> {
+    fn table(&self) -> &str {
+        &self.name
+    }
+
+    fn tables(&self) -> &Tables {
+        &self.site
+    }
+
+    async fn list(&self) -> Result<Vec<T>, APIError> {
+        Ok(self.read()?.values().cloned().collect())
+    }
+
+    async fn page(&self, query: &ListQuery) -> Result<Page<T>, APIError> {
+        query.apply(self.list().await?)
+    }
+
+    async fn find(&self, fields: &[(&'static str, &str)]) -> Result<Vec<T>, APIError> {
+        let mut found = Vec::new();
+        for record in self.read()?.values() {
+            let value = serde_json::to_value(record).map_err(|_| APIError::InternalServerError)?;
+            if fields
+                .iter()
+                .all(|(field, expected)| value.get(field).is_some_and(|value| scalar_eq(value, expected)))
+            {
+                found.push(record.clone());
+            }
+        }
+        Ok(found)
+    }
+
+    async fn last_created(&self, fields: &[(&'static str, &str)]) -> Result<Option<DateTime<Utc>>, APIError> {
+        Ok(self.find(fields).await?.iter().filter_map(Record::created_at).max())
+    }
+
+    async fn get(&self, id: &str) -> Result<Option<T>, APIError> {
+        Ok(self.read()?.get(id).cloned())
+    }
+
+    async fn get_stamped(&self, id: &str) -> Result<Option<(T, Stamp)>, APIError> {
+        Ok(self.read()?.get(id).map(|record| (record.clone(), stamp(record))))
+    }
+
+    async fn create(&self, data: T) -> Result<T, APIError> {
+        insert(&self.name, &mut *self.write()?, data)
+    }
+
+    async fn update(&self, id: &str, data: T) -> Result<Option<T>, APIError> {
+        Ok(replace(&mut *self.write()?, id, data))
+    }
+
+    async fn update_if(&self, id: &str, field: &'static str, expected: &str, mut data: T) -> Result<Option<T>, APIError> {
+        let mut table = self.write()?;
+        let Some(existing) = table.get_mut(id) else {
+            return Ok(None);
+        };
+        let value = serde_json::to_value(&*existing).map_err(|_| APIError::InternalServerError)?;
+        if !value.get(field).is_some_and(|value| scalar_eq(value, expected)) {
+            return Ok(None);
+        }
+
+        data.set_id(id.to_string());
+        *existing = data.clone();
+        Ok(Some(data))
+    }
+
+    async fn merge(&self, id: &str, patch: Value) -> Result<Option<T>, APIError> {
+        merge(&mut *self.write()?, id, &patch)
+    }
+
+    async fn delete(&self, id: &str) -> Result<Option<T>, APIError> {
+        Ok(self.write()?.remove(id))
+    }
+
+    async fn drop_table(&self) -> Result<(), APIError> {
+        self.write()?.clear();
+        Ok(())
+    }
+
+    async fn reorder(&self, ids: &[String]) -> Result<(), APIError> {
+        let mut table = self.write()?;
+        for (position, id) in ids.iter().enumerate() {
+            if let Some(record) = table.get_mut(id) {
+                record.set_position(position as u32);
+            }
+        }
+        Ok(())
+    }
+
+    /// The record's table and its revisions table stay locked from the stamp
+    /// check until the revision is stored, so no other write lands in
+    /// between. A duplicate revision id, the one way storing the revision
+    /// can fail, is ruled out before the record is written.
+    async fn commit(&self, write: Write<T>, mut revision: Revision, expected: Option<&Stamp>) -> Result<Option<T>, APIError> {
+        let name = self.site.site().revisions_table::<T>();
+        let revisions = self.store.table::<Revision>(&name);
+        let mut table = self.write()?;
+        let mut history = revisions.write().map_err(|_| APIError::InternalServerError)?;
+
+        if let (Some(expected), Some(existing)) = (expected, write.id().and_then(|id| table.get(id)))
+            && stamp(existing) != *expected
+        {
+            return Err(APIError::PreconditionFailed);
+        }
+        let revision_id = vacant_id(&name, &history, &revision)?;
+
+        let written = match write {
+            Write::Create(data) => Some(insert(&self.name, &mut table, data)?),
+            Write::Update(id, data) => replace(&mut table, &id, data),
+            Write::Merge(id, patch) => merge(&mut table, &id, &patch)?,
+        };
+        if written.is_some() {
+            revision.set_id(revision_id.clone());
+            history.insert(revision_id, revision);
+        }
+        Ok(written)
+    }
+}
@@ -0,0 +1,281 @@
+mod cached;
+mod memory;
+mod surreal;
+
+use std::sync::Arc;
+
+use actix_web::web;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use serde_json::Value;
+use surrealdb::{Surreal, engine::any::Any};
+
+use crate::error::APIError;
+use crate::models::Record;
+use crate::models::listing::{ListQuery, Page};
+use crate::models::about::{About, Certificate, Contact, Education, ProgLanguage, SpokenLanguage, TechStack};
+use crate::models::site::Site;
+use crate::models::experience::Experience;
+use crate::models::post::Post;
+use crate::models::project::Project;
+use crate::models::revision::Revision;
+use crate::clock::Clock;
+use crate::services::schedule_service::Scheduler;
+use crate::services::translation_service::Translations;
+use crate::services::trash_service::Trash;
+
+pub use cached::{CachedRepository, ReadCache};
+pub use memory::{MemoryRepository, MemoryStore};
+pub use surreal::{SurrealRepository, SurrealStore};
+
+/// Storage for a single table of records.
+#[async_trait]
+pub trait Repository<T: Record>: Send + Sync {
+    /// The table the records are stored in: `T::TABLE`, or a site's copy of it.
+    fn table(&self) -> &str;
+    /// The other tables of the same site.
+    fn tables(&self) -> &Tables;
+    async fn list(&self) -> Result<Vec<T>, APIError>;
+    /// One page of a [`ListQuery`], filtered, sorted and sliced by the store.
+    async fn page(&self, query: &ListQuery) -> Result<Page<T>, APIError>;
+    /// The records whose `fields` equal the given values, e.g. the revisions
+    /// of one record, which SurrealDB finds through an index.
+    async fn find(&self, fields: &[(&'static str, &str)]) -> Result<Vec<T>, APIError>;
+    /// The newest [`created_at`](Record::created_at) of the records whose
+    /// `fields` equal the given values, without loading the records, e.g.
+    /// when a collection was last written.
+    async fn last_created(&self, fields: &[(&'static str, &str)]) -> Result<Option<DateTime<Utc>>, APIError>;
+    async fn get(&self, id: &str) -> Result<Option<T>, APIError>;
+    /// Reads a record, never from a cache, with a [`Stamp`] of its stored
+    /// form that a later [`commit`](Self::commit) can be made conditional on.
+    async fn get_stamped(&self, id: &str) -> Result<Option<(T, Stamp)>, APIError>;
+    /// Creates a record, using `data.id()` as the record id when present.
+    async fn create(&self, data: T) -> Result<T, APIError>;
+    async fn update(&self, id: &str, data: T) -> Result<Option<T>, APIError>;
+    /// Replaces a record only while its `field` still equals `expected`, in
+    /// one step, so two writers cannot both replace the same version. `None`
+    /// when the record is missing or was changed.
+    async fn update_if(&self, id: &str, field: &'static str, expected: &str, data: T) -> Result<Option<T>, APIError>;
+    /// Applies a JSON Merge Patch to an existing record.
+    async fn merge(&self, id: &str, patch: Value) -> Result<Option<T>, APIError>;
+    async fn delete(&self, id: &str) -> Result<Option<T>, APIError>;
+    /// Deletes the table with every record in it, e.g. once its site is gone.
+    async fn drop_table(&self) -> Result<(), APIError>;
+    /// Sets the `position` of each record to its index in `ids`, atomically.
+    async fn reorder(&self, ids: &[String]) -> Result<(), APIError>;
+    /// Applies `write` and stores `revision` in the [revisions](Tables::revisions)
+    /// of `T` in one transaction, so no write goes missing from the history.
+    /// The revision is only stored when the record exists. With an `expected`
+    /// stamp, an existing record that was changed since it was read is left
+    /// alone and the commit fails with 412 Precondition Failed.
+    async fn commit(&self, write: Write<T>, revision: Revision, expected: Option<&Stamp>) -> Result<Option<T>, APIError>;
+}
+
+/// A fingerprint of a record as it is stored, see [`Repository::get_stamped`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stamp(pub(crate) String);
+
+/// A write to one record, see [`Repository::commit`].
+pub enum Write<T> {
+    /// Creates a record, which must already have its id
+    Create(T),
+    Update(String, T),
+    /// Applies a JSON Merge Patch
+    Merge(String, Value),
+}
+
+impl<T: Record> Write<T> {
+    pub fn id(&self) -> Option<&str> {
+        match self {
+            Write::Create(data) => data.id(),
+            Write::Update(id, _) | Write::Merge(id, _) => Some(id),
+        }
+    }
+}
+
+/// The repositories of one site, for work that spans tables.
+#[derive(Clone)]
+pub struct Tables {
+    store: Store,
+    site: Site,
+}
+
+impl Tables {
+    pub fn site(&self) -> &Site {
+        &self.site
+    }
+
+    pub fn repository<T: Record>(&self) -> Arc<dyn Repository<T>> {
+        self.store.repository_for::<T>(&self.site)
+    }
+
+    /// The revisions of `T` records, see [`Site::revisions_table`].
+    pub fn revisions<T: Record>(&self) -> Arc<dyn Repository<Revision>> {
+        if T::SITE_SCOPED {
+            self.repository::<Revision>()
+        } else {
+            self.store.repository::<Revision>()
+        }
+    }
+}
+
+/// The records a record points at in other tables of its site, collected by
+/// [`Record::references`] and checked before the record is written.
+pub struct References<'a> {
+    tables: &'a Tables,
+    checks: Vec<BoxFuture<'static, Result<(), APIError>>>,
+}
+
+impl<'a> References<'a> {
+    pub fn new(tables: &'a Tables) -> Self {
+        Self {
+            tables,
+            checks: Vec::new(),
+        }
+    }
+
+    /// Requires each of `ids` to be a `T` record that is not in the trash.
+    pub fn to<T: Record>(&mut self, ids: &[String]) {
+        let repo = self.tables.repository::<T>();
+        let ids = ids.to_vec();
+        self.checks.push(Box::pin(async move {
+            for id in ids {
+                if repo.get(&id).await?.is_none_or(|record| record.deleted_at().is_some()) {
+                    return Err(APIError::Validation(format!("unknown {} `{}`", T::TABLE, id)));
+                }
+            }
+            Ok(())
+        }));
+    }
+
+    /// Fails with a validation error naming the first missing record.
+    pub async fn check(self) -> Result<(), APIError> {
+        for check in self.checks {
+            check.await?;
+        }
+        Ok(())
+    }
+}
+
+/// Something built from the repository of every content model, like the
+/// [`Trash`] or the [`Scheduler`]'s work list. See [`Store::visit`].
+pub trait ModelVisitor {
+    fn visit<T: Record>(&mut self, repo: Arc<dyn Repository<T>>);
+}
+
+/// The backing store that repositories are created from.
+#[derive(Clone)]
+pub enum Store {
+    Surreal(SurrealStore),
+    Memory(MemoryStore),
+    /// Another store with a [`ReadCache`] in front of it
+    Cached(Box<Store>, ReadCache),
+}
+
+impl Store {
+    pub fn in_memory() -> Self {
+        Store::Memory(MemoryStore::default())
+    }
+
+    pub fn surreal(db: Surreal<Any>) -> Self {
+        Store::Surreal(SurrealStore::new(db))
+    }
+
+    pub fn cached(self, cache: ReadCache) -> Self {
+        Store::Cached(Box::new(self), cache)
+    }
+
+    /// The repository of `T` for the default site.
+    pub fn repository<T: Record>(&self) -> Arc<dyn Repository<T>> {
+        self.repository_for::<T>(&Site::default_for(None))
+    }
+
+    pub fn repository_for<T: Record>(&self, site: &Site) -> Arc<dyn Repository<T>> {
+        match self {
+            Store::Surreal(store) => Arc::new(store.repository::<T>(self.tables(site))),
+            Store::Memory(store) => Arc::new(store.repository::<T>(self.tables(site))),
+            Store::Cached(store, _) if !T::CACHED => store.repository_for::<T>(site),
+            Store::Cached(store, cache) => Arc::new(CachedRepository::new(store.repository_for::<T>(site), cache.clone())),
+        }
+    }
+
+    pub fn tables(&self, site: &Site) -> Tables {
+        Tables {
+            store: self.clone(),
+            site: site.clone(),
+        }
+    }
+
+    /// Hands `visitor` the repository of every content model of `site`. This
+    /// is the one list of models; a new model only has to be added here to
+    /// get a trash, translation reports and scheduled publishing. Models that
+    /// are not [`SITE_SCOPED`](Record::SITE_SCOPED) belong to the default site.
+    pub fn visit(&self, site: &Site, visitor: &mut impl ModelVisitor) {
+        fn model<T: Record>(store: &Store, site: &Site, visitor: &mut impl ModelVisitor) {
+            if T::SITE_SCOPED || site.default {
+                visitor.visit(store.repository_for::<T>(site));
+            }
+        }
+
+        model::<About>(self, site, visitor);
+        model::<Education>(self, site, visitor);
+        model::<Contact>(self, site, visitor);
+        model::<Certificate>(self, site, visitor);
+        model::<ProgLanguage>(self, site, visitor);
+        model::<SpokenLanguage>(self, site, visitor);
+        model::<TechStack>(self, site, visitor);
+        model::<Project>(self, site, visitor);
+        model::<Experience>(self, site, visitor);
+        model::<Post>(self, site, visitor);
+    }
+
+    /// Drops the tables of a site whose About record was purged, revisions
+    /// included. Tables shared by all sites stay, and so do those of the
+    /// default site.
+    pub async fn drop_tables(&self, site: &Site) -> Result<(), APIError> {
+        #[derive(Default)]
+        struct Drops(Vec<BoxFuture<'static, Result<(), APIError>>>);
+
+        impl ModelVisitor for Drops {
+            fn visit<T: Record>(&mut self, repo: Arc<dyn Repository<T>>) {
+                self.0.push(Box::pin(async move { repo.drop_table().await }));
+            }
+        }
+
+        if site.default {
+            return Ok(());
+        }
+        let mut drops = Drops::default();
+        self.visit(site, &mut drops);
+        for drop in drops.0 {
+            drop.await?;
+        }
+        self.repository_for::<Revision>(site).drop_table().await
+    }
+
+    /// The trash of every soft-deletable model of `site`.
+    pub fn trash(&self, site: &Site) -> Trash {
+        let mut trash = Trash::default();
+        self.visit(site, &mut trash);
+        trash
+    }
+
+    /// The models of `site` with [`Localized`](crate::models::locale::Localized) fields.
+    pub fn translations(&self, site: &Site) -> Translations {
+        let mut translations = Translations::default();
+        self.visit(site, &mut translations);
+        translations
+    }
+
+    /// The publishing schedule of every site.
+    pub fn scheduler(&self, clock: Arc<dyn Clock>) -> Scheduler {
+        Scheduler::new(self.clone(), clock)
+    }
+
+    /// Registers the store as `web::Data<Store>`, which handlers get
+    /// site-scoped repositories from (see [`Repo`](crate::routes::site::Repo)).
+    pub fn configure(&self, cfg: &mut web::ServiceConfig) {
+        cfg.app_data(web::Data::new(self.clone()));
+    }
+}
@@ -0,0 +1,397 @@
+use std::collections::{BTreeMap, HashSet};
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use surrealdb::{RecordId, Surreal, engine::any::Any};
+
+use crate::error::APIError;
+use crate::models::listing::{Filter, ListQuery, Missing, Order, Page};
+use crate::models::revision::Revision;
+use crate::models::{Record, document};
+use crate::repository::{Repository, Stamp, Tables, Write};
+
+/// A SurrealDB connection, shared between every repository created from the
+/// same store, and the revisions tables it has already indexed.
+#[derive(Clone)]
+pub struct SurrealStore {
+    db: Surreal<Any>,
+    indexed: Arc<Mutex<HashSet<String>>>,
+}
+
+impl SurrealStore {
+    pub fn new(db: Surreal<Any>) -> Self {
+        Self {
+            db,
+            indexed: Arc::default(),
+        }
+    }
+
+    pub fn repository<T: Record>(&self, site: Tables) -> SurrealRepository<T> {
+        SurrealRepository {
+            db: self.db.clone(),
+            indexed: self.indexed.clone(),
+            table: site.site().table::<T>(),
+            site,
+            _model: PhantomData,
+        }
+    }
+}
+
+pub struct SurrealRepository<T> {
+    db: Surreal<Any>,
+    /// The revisions tables of the store that have their index
+    indexed: Arc<Mutex<HashSet<String>>>,
+    table: String,
+    site: Tables,
+    _model: PhantomData<fn() -> T>,
+}
+
+impl<T> SurrealRepository<T> {
+    /// Defines the index that serves `find` on the history of a record the
+    /// first time the store writes to the revisions table `table`, rather
+    /// than with every write.
+    async fn index_revisions(&self, table: &str) -> Result<(), APIError> {
+        if self.indexed.lock().map_err(|_| APIError::InternalServerError)?.contains(table) {
+            return Ok(());
+        }
+
+        self.db
+            .query(format!("DEFINE INDEX IF NOT EXISTS record ON TABLE {} FIELDS collection, record", ident(table)))
+            .await
+            .map_err(db_error)?
+            .check()
+            .map_err(db_error)?;
+        self.indexed
+            .lock()
+            .map_err(|_| APIError::InternalServerError)?
+            .insert(table.to_string());
+        Ok(())
+    }
+}
+
+/// Maps a SurrealDB error to an [`APIError`]. Creating a record that
+/// already exists is a client mistake, reported as 409 Conflict; embedded
+/// engines say so with a typed error, remote engines and failed
+/// transactions only in the message.
+fn db_error(e: surrealdb::Error) -> APIError {
+    match &e {
+        surrealdb::Error::Db(surrealdb::error::Db::RecordExists { .. }) => APIError::Conflict(e.to_string()),
+        _ if e.to_string().contains("already exists") => APIError::Conflict(e.to_string()),
+        _ => APIError::Database(e.to_string()),
+    }
+}
+
+/// `name` quoted as a SurrealQL identifier.
+fn ident(name: &str) -> String {
+    format!("`{}`", name.replace('`', ""))
+}
+
+/// A dot separated field path as a SurrealQL idiom.
+fn path(field: &str) -> String {
+    field.split('.').map(ident).collect::<Vec<_>>().join(".")
+}
+
+/// `fields` as SurrealQL conditions joined by AND, and the values they are bound to.
+fn conditions(fields: &[(&'static str, &str)]) -> (String, BTreeMap<String, String>) {
+    let conditions: Vec<String> = (0..fields.len())
+        .map(|index| format!("{} = $value{}", ident(fields[index].0), index))
+        .collect();
+    let values = fields
+        .iter()
+        .enumerate()
+        .map(|(index, (_, value))| (format!("value{}", index), value.to_string()))
+        .collect();
+    (conditions.join(" AND "), values)
+}
+
+#[derive(Deserialize)]
+struct Created {
+    created_at: Option<DateTime<Utc>>,
+}
+
+/// Hashes the stored form of the record in `$current`.
+const STAMP: &str = "crypto::sha256(<string> $current)";
+
+/// Thrown by [`commit`](Repository::commit) when the record no longer has the expected stamp.
+const STALE: &str = "record changed since it was read";
+
+/// What SurrealDB answers for the statements of a failed transaction that did not fail it.
+const NOT_EXECUTED: &str = "not executed due to a failed transaction";
+
+#[derive(Deserialize)]
+#[serde(bound = "T: Record")]
+struct Stamped<T> {
+    record: T,
+    stamp: String,
+}
+
+#[derive(Serialize)]
+struct Position {
+    record: RecordId,
+    position: u32,
+}
+
+#[async_trait]
+impl<T: Record> Repository<T> for SurrealRepository<T> {
+    fn table(&self) -> &str {
+        &self.table
+    }
+
+    fn tables(&self) -> &Tables {
+        &self.site
+    }
+
+    async fn list(&self) -> Result<Vec<T>, APIError> {
+        self.db
+            .select(self.table.as_str())
+            .await
+            .map_err(db_error)
+    }
+
+    /// Stored values are compared to the filters as strings, like
+    /// [`scalar_eq`](crate::models::listing::scalar_eq) does in process.
+    async fn page(&self, query: &ListQuery) -> Result<Page<T>, APIError> {
+        let mut conditions = vec!["!deleted_at".to_string()];
+        if query.published_only {
+            conditions.push("(status ?? 'published') = 'published'".to_string());
+        }
+        let mut values = BTreeMap::new();
+        for (index, (_, filter, value)) in query.filters.iter().enumerate() {
+            conditions.push(match filter {
+                Filter::Equals(field) => format!("{field} != NONE AND <string> {field} = $filter{index}", field = path(field)),
+                Filter::Contains(field) => format!(
+                    "array::map({} ?? [], |$item| <string> $item) CONTAINS $filter{index}",
+                    path(field)
+                ),
+            });
+            values.insert(format!("filter{index}"), value.clone());
+        }
+
+        // Records without a field sort as the smallest values, unless the
+        // key places them with a computed flag
+        let mut flags = Vec::new();
+        let mut order = Vec::new();
+        for (index, key) in query.sort_keys::<T>().iter().enumerate() {
+            if let Some(missing) = key.missing {
+                flags.push(format!(", ({field} = NONE OR {field} = NULL) AS __missing{index}", field = path(key.field)));
+                order.push(format!("__missing{index} {}", if missing == Missing::Last { "ASC" } else { "DESC" }));
+            }
+            order.push(format!("{} {}", path(key.field), if key.order == Order::Asc { "ASC" } else { "DESC" }));
+        }
+        order.push("id ASC".to_string());
+
+        let mut response = self
+            .db
+            .query(format!(
+                "SELECT *{} FROM {} WHERE {} ORDER BY {} LIMIT $limit START $start",
+                flags.concat(),
+                ident(&self.table),
+                conditions.join(" AND "),
+                order.join(", "),
+            ))
+            .bind(values)
+            // One more than asked for tells whether there is a next page
+            .bind(("limit", query.limit + 1))
+            .bind(("start", query.cursor))
+            .await
+            .map_err(db_error)?;
+        let mut items: Vec<T> = response.take(0).map_err(db_error)?;
+
+        let next_cursor = (items.len() > query.limit).then(|| (query.cursor + query.limit).to_string());
+        items.truncate(query.limit);
+        Ok(Page { items, next_cursor })
+    }
+
+    async fn find(&self, fields: &[(&'static str, &str)]) -> Result<Vec<T>, APIError> {
+        if fields.is_empty() {
+            return self.list().await;
+        }
+
+        let (conditions, values) = conditions(fields);
+        let mut response = self
+            .db
+            .query(format!("SELECT * FROM {} WHERE {}", ident(&self.table), conditions))
+            .bind(values)
+            .await
+            .map_err(db_error)?;
+        response.take(0).map_err(db_error)
+    }
+
+    /// `created_at` is stored as an RFC 3339 string, whose order is the
+    /// order in time down to the second.
+    async fn last_created(&self, fields: &[(&'static str, &str)]) -> Result<Option<DateTime<Utc>>, APIError> {
+        let (conditions, values) = conditions(fields);
+        let filter = if fields.is_empty() { String::new() } else { format!("WHERE {}", conditions) };
+        let mut response = self
+            .db
+            .query(format!(
+                "SELECT created_at FROM {} {} ORDER BY created_at DESC LIMIT 1",
+                ident(&self.table),
+                filter
+            ))
+            .bind(values)
+            .await
+            .map_err(db_error)?;
+        let newest: Option<Created> = response.take(0).map_err(db_error)?;
+        Ok(newest.and_then(|newest| newest.created_at))
+    }
+
+    async fn get(&self, id: &str) -> Result<Option<T>, APIError> {
+        self.db
+            .select(RecordId::from((self.table.as_str(), id)))
+            .await
+            .map_err(db_error)
+    }
+
+    async fn get_stamped(&self, id: &str) -> Result<Option<(T, Stamp)>, APIError> {
+        let mut response = self
+            .db
+            .query(format!(
+                "LET $current = (SELECT * FROM ONLY $record);
+                IF $current THEN {{ record: $current, stamp: {STAMP} }} END;"
+            ))
+            .bind(("record", RecordId::from((self.table.as_str(), id))))
+            .await
+            .map_err(db_error)?;
+        let stamped: Option<Stamped<T>> = response.take(1).map_err(db_error)?;
+        Ok(stamped.map(|stamped| (stamped.record, Stamp(stamped.stamp))))
+    }
+
+    async fn create(&self, data: T) -> Result<T, APIError> {
+        let created: Option<T> = match data.id() {
+            Some(id) => self.db.create(RecordId::from((self.table.as_str(), id))).content(data).await,
+            None => self.db.create(self.table.as_str()).content(data).await,
+        }
+        .map_err(db_error)?;
+
+        created.ok_or(APIError::InternalServerError)
+    }
+
+    async fn update(&self, id: &str, data: T) -> Result<Option<T>, APIError> {
+        self.db
+            .update(RecordId::from((self.table.as_str(), id)))
+            .content(data)
+            .await
+            .map_err(db_error)
+    }
+
+    async fn update_if(&self, id: &str, field: &'static str, expected: &str, data: T) -> Result<Option<T>, APIError> {
+        let mut response = self
+            .db
+            .query(format!("UPDATE $record CONTENT $data WHERE {} = $expected", ident(field)))
+            .bind(("record", RecordId::from((self.table.as_str(), id))))
+            .bind(("data", document(&data)?))
+            .bind(("expected", expected.to_string()))
+            .await
+            .map_err(db_error)?;
+        response.take(0).map_err(db_error)
+    }
+
+    async fn merge(&self, id: &str, patch: Value) -> Result<Option<T>, APIError> {
+        self.db
+            .update(RecordId::from((self.table.as_str(), id)))
+            .merge(patch)
+            .await
+            .map_err(db_error)
+    }
+
+    async fn delete(&self, id: &str) -> Result<Option<T>, APIError> {
+        self.db
+            .delete(RecordId::from((self.table.as_str(), id)))
+            .await
+            .map_err(db_error)
+    }
+
+    async fn drop_table(&self) -> Result<(), APIError> {
+        self.db
+            .query(format!("REMOVE TABLE IF EXISTS {}", ident(&self.table)))
+            .await
+            .map_err(db_error)?
+            .check()
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn reorder(&self, ids: &[String]) -> Result<(), APIError> {
+        let positions: Vec<Position> = ids
+            .iter()
+            .enumerate()
+            .map(|(position, id)| Position {
+                record: RecordId::from((self.table.as_str(), id.as_str())),
+                position: position as u32,
+            })
+            .collect();
+
+        let response = self
+            .db
+            .query(
+                "BEGIN TRANSACTION;
+                FOR $item IN $positions { UPDATE $item.record SET position = $item.position; };
+                COMMIT TRANSACTION;",
+            )
+            .bind(("positions", positions))
+            .await
+            .map_err(db_error)?;
+
+        response
+            .check()
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn commit(&self, write: Write<T>, revision: Revision, expected: Option<&Stamp>) -> Result<Option<T>, APIError> {
+        let (id, statement, data) = match write {
+            Write::Create(data) => (
+                data.id().ok_or(APIError::InternalServerError)?.to_string(),
+                "CREATE $record CONTENT $data",
+                document(&data)?,
+            ),
+            Write::Update(id, data) => (id, "UPDATE $record CONTENT $data", document(&data)?),
+            Write::Merge(id, patch) => (id, "UPDATE $record MERGE $data", patch),
+        };
+        let revisions = self.site.site().revisions_table::<T>();
+        self.index_revisions(&revisions).await?;
+
+        let response = self
+            .db
+            .query(format!(
+                "BEGIN TRANSACTION;
+                LET $current = (SELECT * FROM ONLY $record);
+                IF $current AND $expected {{ IF {STAMP} != $expected {{ THROW '{STALE}'; }}; }};
+                LET $written = {statement};
+                IF $written {{ CREATE {revisions} CONTENT $revision; }};
+                COMMIT TRANSACTION;
+                SELECT * FROM $record;",
+                revisions = ident(&revisions),
+            ))
+            .bind(("record", RecordId::from((self.table.as_str(), id.as_str()))))
+            .bind(("data", data))
+            .bind(("revision", revision))
+            .bind(("expected", expected.map(|stamp| stamp.0.clone())))
+            .await
+            .map_err(db_error)?;
+
+        let last = response.num_statements() - 1;
+        let mut response = response;
+        let mut errors: Vec<_> = response.take_errors().into_iter().collect();
+        errors.sort_by_key(|(index, _)| *index);
+        // Every other statement of a failed transaction only says that it failed
+        let cause = errors
+            .iter()
+            .position(|(_, e)| !e.to_string().contains(NOT_EXECUTED))
+            .unwrap_or_default();
+        if !errors.is_empty() {
+            let (_, cause) = errors.swap_remove(cause);
+            return Err(if cause.to_string().contains(STALE) {
+                APIError::PreconditionFailed
+            } else {
+                db_error(cause)
+            });
+        }
+        response.take(last).map_err(db_error)
+    }
+}
@@ -0,0 +1,179 @@
+use actix_web::http::header::{self, EntityTag, HttpDate, IfMatch, IfModifiedSince, IfNoneMatch};
+use actix_web::{FromRequest, HttpMessage, HttpRequest, HttpResponse, dev::Payload};
+use futures::future::{Ready, ready};
+use serde::Serialize;
+
+use crate::error::APIError;
+use crate::models::locale::Locale;
+use crate::models::status::Viewer;
+use crate::models::{Record, version};
+use crate::repository::{Repository, Stamp};
+use crate::routes::site::SITE_HEADER;
+
+/// The `If-Match` header of a write request. Rejects the request with
+/// 428 Precondition Required when it is missing.
+pub struct Precondition(pub IfMatch);
+
+impl FromRequest for Precondition {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        match req.get_header::<IfMatch>() {
+            Some(if_match) => ready(Ok(Precondition(if_match))),
+            None => ready(Err(APIError::PreconditionRequired.into())),
+        }
+    }
+}
+
+impl Precondition {
+    /// Fails with 412 Precondition Failed unless `current` is the version the
+    /// client saw, in whichever locale it was served.
+    pub fn check<T: Record>(&self, current: &T) -> Result<(), APIError> {
+        let matches = match &self.0 {
+            IfMatch::Any => true,
+            IfMatch::Items(tags) => {
+                let current = version(current);
+                tags.iter().filter(|tag| !tag.weak).any(|tag| {
+                    tag.tag() == current
+                        || tag
+                            .tag()
+                            .strip_prefix(current.as_str())
+                            .and_then(|rest| rest.strip_prefix('-'))
+                            .is_some_and(|locale| !locale.is_empty())
+                })
+            }
+        };
+
+        if matches {
+            Ok(())
+        } else {
+            Err(APIError::PreconditionFailed)
+        }
+    }
+
+    /// Loads the record, bypassing any cache, and [`check`](Self::check)s it;
+    /// 404 when it does not exist or is in the trash. Returns the stamp the
+    /// write must be [committed](Repository::commit) with, so a write that
+    /// lands in between still fails with 412; `If-Match: *` needs none.
+    pub async fn verify<T: Record>(&self, repo: &dyn Repository<T>, id: &str) -> Result<Option<Stamp>, APIError> {
+        let (current, stamp) = repo
+            .get_stamped(id)
+            .await?
+            .filter(|(current, _)| current.deleted_at().is_none())
+            .ok_or(APIError::NotFound)?;
+        self.check(&current)?;
+        Ok(match self.0 {
+            IfMatch::Any => None,
+            IfMatch::Items(_) => Some(stamp),
+        })
+    }
+}
+
+pub fn etag<T: Record>(record: &T) -> header::ETag {
+    header::ETag(EntityTag::new_strong(version(record)))
+}
+
+/// The tag of `record` as served in `locale`. Models with localized fields
+/// are served differently per locale, so their tag names it.
+pub fn entity_tag_in<T: Record>(record: &T, locale: &Locale) -> EntityTag {
+    match locale {
+        Locale::One(locale) if !T::LOCALIZED.is_empty() => EntityTag::new_strong(format!("{}-{}", version(record), locale)),
+        _ => EntityTag::new_strong(version(record)),
+    }
+}
+
+pub fn etag_in<T: Record>(record: &T, locale: &Locale) -> header::ETag {
+    header::ETag(entity_tag_in(record, locale))
+}
+
+/// The `Cache-Control` value sent with a public read endpoint. Generic
+/// resources look it up from the resource's app data.
+#[derive(Debug, Clone, Copy)]
+pub struct CachePolicy(pub &'static str);
+
+impl CachePolicy {
+    pub const DEFAULT: CachePolicy = CachePolicy("public, max-age=60, stale-while-revalidate=300");
+    /// Profile data like the About record or the tech stacks changes a few
+    /// times a year, so caches keep it longer and revalidate in the background.
+    pub const PROFILE: CachePolicy = CachePolicy("public, max-age=300, stale-while-revalidate=86400");
+    pub const NO_STORE: CachePolicy = CachePolicy("no-store");
+
+    pub fn of(req: &HttpRequest) -> CachePolicy {
+        req.app_data::<CachePolicy>().copied().unwrap_or(CachePolicy::DEFAULT)
+    }
+
+    /// Responses for admins and previews may include drafts, so they are never stored.
+    pub fn for_viewer(self, viewer: Viewer) -> CachePolicy {
+        match viewer {
+            Viewer::Public => self,
+            Viewer::Admin | Viewer::Preview => CachePolicy::NO_STORE,
+        }
+    }
+}
+
+/// Whether the client's copy is current, judged by `If-None-Match` or,
+/// when that is absent, `If-Modified-Since`.
+pub fn is_fresh(req: &HttpRequest, tag: &EntityTag, modified: Option<HttpDate>) -> bool {
+    match req.get_header::<IfNoneMatch>() {
+        Some(IfNoneMatch::Any) => true,
+        Some(IfNoneMatch::Items(tags)) => tags.iter().any(|t| t.weak_eq(tag)),
+        None => modified.is_some_and(|modified| {
+            req.get_header::<IfModifiedSince>()
+                .is_some_and(|IfModifiedSince(since)| modified <= since)
+        }),
+    }
+}
+
+/// Answers 304 Not Modified when the client's copy is current, otherwise `body()`.
+/// Both carry the `ETag` and the `Cache-Control` of `policy`, and vary by the
+/// headers the [`Site`](crate::models::site::Site) is resolved from.
+pub fn conditional(
+    req: &HttpRequest,
+    tag: EntityTag,
+    modified: Option<HttpDate>,
+    policy: CachePolicy,
+    body: impl FnOnce(&mut actix_web::HttpResponseBuilder) -> HttpResponse,
+) -> HttpResponse {
+    let fresh = is_fresh(req, &tag, modified);
+    let mut response = if fresh {
+        HttpResponse::NotModified()
+    } else {
+        HttpResponse::Ok()
+    };
+
+    response
+        .insert_header(header::ETag(tag))
+        .insert_header((header::CACHE_CONTROL, policy.0))
+        .append_header((header::VARY, "Host"))
+        .append_header((header::VARY, SITE_HEADER));
+    if let Some(modified) = modified {
+        response.insert_header(header::LastModified(modified));
+    }
+
+    if fresh { response.finish() } else { body(&mut response) }
+}
+
+/// Serves `body` as JSON with a strong `ETag` over its serialized form.
+pub fn cached_json<B: Serialize>(req: &HttpRequest, body: &B, policy: CachePolicy) -> HttpResponse {
+    let Ok(bytes) = serde_json::to_vec(body) else {
+        return HttpResponse::InternalServerError().finish();
+    };
+    let tag = EntityTag::new_strong(crate::utils::etag(&bytes));
+
+    conditional(req, tag, None, policy, |response| {
+        response.content_type("application/json").body(bytes)
+    })
+}
+
+/// Serves a single record as JSON, tagged with its [`version`] in `locale`.
+pub fn cached_record<T: Record, B: Serialize>(
+    req: &HttpRequest,
+    record: &T,
+    locale: &Locale,
+    body: &B,
+    policy: CachePolicy,
+) -> HttpResponse {
+    let tag = entity_tag_in(record, locale);
+    conditional(req, tag, None, policy, |response| response.json(body))
+}
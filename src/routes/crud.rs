@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use actix_web::{HttpRequest, HttpResponse, Result, guard, web};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::auth::preview::Preview;
+use crate::auth::user::AuthenticatedUser;
+use crate::error::APIError;
+use crate::models::Record;
+use crate::models::listing::ListQuery;
+use crate::models::locale::Locale;
+use crate::models::status::Viewer;
+use crate::routes::site::Repo;
+use crate::routes::conditional::{CachePolicy, Precondition, cached_json, cached_record, etag_in};
+use crate::routes::item::ItemPath;
+use crate::routes::locale::localized;
+use crate::routes::merge_patch::MergePatch;
+use crate::routes::record_body::{RecordBody, Replacement};
+use crate::routes::publishing::PublishRoutes;
+use crate::routes::revisions::RevisionRoutes;
+use crate::services::crud_service;
+
+/// Registers the list/get/create/update/patch/delete endpoints for a [`Record`]
+/// under a path prefix. Records are stored in `T::TABLE`; reads are public
+/// and writes require an [`AuthenticatedUser`]. The list endpoint accepts a
+/// [`ListQuery`] and answers with a [`Page`](crate::models::listing::Page).
+/// The public only reads published records, or a draft through a
+/// [`Preview`] token; admins also see drafts and can [publish](PublishRoutes) them.
+/// Reads carry a strong `ETag` and honor `If-None-Match`; PUT/PATCH/DELETE
+/// require a matching `If-Match` ([`Precondition`]). Every write is recorded
+/// as a [`Revision`](crate::models::revision::Revision), browsable through
+/// [`RevisionRoutes`]. Responses are written in the negotiated [`Locale`].
+/// Models whose slug doubles as the record id, like posts, are addressed by slug.
+///
+/// ```ignore
+/// CrudResource::<TechStack>::new("/tech-stacks").register(cfg);
+/// ```
+pub struct CrudResource<T> {
+    path: &'static str,
+    list_suffix: &'static str,
+    orderable: bool,
+    cache: CachePolicy,
+    views: Views<T>,
+    _model: PhantomData<fn() -> T>,
+}
+
+/// Shapes a read response from a record and its JSON, already resolved to
+/// the request's locale.
+pub type Render<T> = fn(&T, Value) -> Value;
+
+/// How a [`CrudResource`] renders single records and list items.
+struct Views<T> {
+    item: Render<T>,
+    list: Render<T>,
+}
+
+impl<T> Clone for Views<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Views<T> {}
+
+impl<T: Record> Views<T> {
+    fn of(req: &HttpRequest) -> Self {
+        req.app_data::<Self>().copied().unwrap_or(Self {
+            item: as_is,
+            list: as_is,
+        })
+    }
+}
+
+fn as_is<T>(_: &T, body: Value) -> Value {
+    body
+}
+
+impl<T: Record> CrudResource<T> {
+    pub fn new(path: &'static str) -> Self {
+        Self {
+            path,
+            list_suffix: "",
+            orderable: false,
+            cache: CachePolicy::DEFAULT,
+            views: Views {
+                item: as_is,
+                list: as_is,
+            },
+            _model: PhantomData,
+        }
+    }
+
+    /// Serves the list endpoint at `{path}{suffix}` instead of `{path}`, e.g. `/about/all`.
+    pub fn list_at(mut self, suffix: &'static str) -> Self {
+        self.list_suffix = suffix;
+        self
+    }
+
+    /// Adds `POST {path}/reorder` for models with a `position`.
+    pub fn orderable(mut self) -> Self {
+        self.orderable = true;
+        self
+    }
+
+    /// Overrides the `Cache-Control` of the public read endpoints.
+    pub fn cache_control(mut self, cache: CachePolicy) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Shapes the body of `GET {path}/{id}`, e.g. adds a post's rendered HTML.
+    pub fn render(mut self, render: Render<T>) -> Self {
+        self.views.item = render;
+        self
+    }
+
+    /// Shapes each item of the list endpoint, e.g. leaves out a post's body.
+    pub fn summarize(mut self, summarize: Render<T>) -> Self {
+        self.views.list = summarize;
+        self
+    }
+
+    pub fn register(self, cfg: &mut web::ServiceConfig) {
+        let item = format!("{}/{{id}}", self.path);
+
+        cfg.service(
+            web::resource(format!("{}{}", self.path, self.list_suffix))
+                .guard(guard::Get())
+                .app_data(self.cache)
+                .app_data(self.views)
+                .to(get_all::<T>),
+        )
+        .route(self.path, web::post().to(create::<T>))
+        .service(
+            web::resource(item.as_str())
+                .guard(guard::Get())
+                .app_data(self.cache)
+                .app_data(self.views)
+                .to(get::<T>),
+        )
+        .route(&item, web::put().to(put::<T>))
+        .route(&item, web::patch().to(patch::<T>))
+        .route(&item, web::delete().to(delete::<T>));
+
+        PublishRoutes::<T>::at(ItemPath::new(&item)).register(cfg);
+        RevisionRoutes::<T>::at(ItemPath::new(&item)).register(cfg);
+
+        if self.orderable {
+            cfg.route(&format!("{}/reorder", self.path), web::post().to(reorder::<T>));
+        }
+    }
+}
+
+async fn get_all<T: Record>(
+    req: HttpRequest,
+    viewer: Viewer,
+    locale: Locale,
+    repo: Repo<T>,
+    params: web::Query<HashMap<String, String>>,
+) -> Result<HttpResponse, APIError> {
+    let query = ListQuery::parse::<T>(&params)?;
+    let views = Views::<T>::of(&req);
+    let page = crud_service::list(repo.get_ref(), &query, viewer)
+        .await?
+        .map(|item| (views.list)(&item, locale.record(&item)));
+    Ok(localized(&locale, cached_json(&req, &page, CachePolicy::of(&req).for_viewer(viewer))))
+}
+
+async fn get<T: Record>(
+    req: HttpRequest,
+    viewer: Viewer,
+    preview: Preview,
+    locale: Locale,
+    repo: Repo<T>,
+    id: web::Path<String>,
+) -> Result<HttpResponse, APIError> {
+    let viewer = preview.viewer_for(viewer, repo.table(), &id);
+    let item = crud_service::view(repo.get_ref(), &id, viewer).await?;
+    let body = (Views::<T>::of(&req).item)(&item, locale.record(&item));
+    Ok(localized(&locale, cached_record(&req, &item, &locale, &body, CachePolicy::of(&req).for_viewer(viewer))))
+}
+
+async fn create<T: Record>(
+    user: AuthenticatedUser,
+    locale: Locale,
+    repo: Repo<T>,
+    RecordBody(payload): RecordBody<T>,
+) -> Result<HttpResponse, APIError> {
+    let item = crud_service::create(repo.get_ref(), payload, &user.0.email).await?;
+    Ok(localized(&locale, HttpResponse::Created().insert_header(etag_in(&item, &locale)).json(locale.record(&item))))
+}
+
+async fn put<T: Record>(
+    user: AuthenticatedUser,
+    locale: Locale,
+    repo: Repo<T>,
+    id: web::Path<String>,
+    precondition: Precondition,
+    Replacement(payload): Replacement,
+) -> Result<HttpResponse, APIError> {
+    let expected = precondition.verify(repo.get_ref(), &id).await?;
+    let item = crud_service::replace(repo.get_ref(), &id, payload, &user.0.email, expected.as_ref()).await?;
+    Ok(localized(&locale, HttpResponse::Ok().insert_header(etag_in(&item, &locale)).json(locale.record(&item))))
+}
+
+async fn patch<T: Record>(
+    user: AuthenticatedUser,
+    locale: Locale,
+    repo: Repo<T>,
+    id: web::Path<String>,
+    precondition: Precondition,
+    MergePatch(patch): MergePatch,
+) -> Result<HttpResponse, APIError> {
+    let expected = precondition.verify(repo.get_ref(), &id).await?;
+    let item = crud_service::patch(repo.get_ref(), &id, patch, &user.0.email, expected.as_ref()).await?;
+    Ok(localized(&locale, HttpResponse::Ok().insert_header(etag_in(&item, &locale)).json(locale.record(&item))))
+}
+
+async fn delete<T: Record>(
+    user: AuthenticatedUser,
+    repo: Repo<T>,
+    id: web::Path<String>,
+    precondition: Precondition,
+) -> Result<HttpResponse, APIError> {
+    let expected = precondition.verify(repo.get_ref(), &id).await?;
+    crud_service::delete(repo.get_ref(), &id, &user.0.email, expected.as_ref()).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[derive(Deserialize)]
+struct ReorderRequest {
+    ids: Vec<String>,
+}
+
+async fn reorder<T: Record>(
+    _user: AuthenticatedUser,
+    repo: Repo<T>,
+    payload: web::Json<ReorderRequest>,
+) -> Result<HttpResponse, APIError> {
+    crud_service::reorder(repo.get_ref(), &payload.ids).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
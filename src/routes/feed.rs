@@ -0,0 +1,104 @@
+use std::time::{Duration, SystemTime};
+
+use actix_web::http::header::{EntityTag, HttpDate};
+use actix_web::{HttpRequest, HttpResponse, Result, get, web};
+use serde::Deserialize;
+
+use crate::error::APIError;
+use crate::models::about::{About, Certificate};
+use crate::models::post::Post;
+use crate::models::revision::Revision;
+use crate::models::site;
+use crate::routes::site::Repo;
+use crate::routes::conditional::{CachePolicy, conditional};
+use crate::services::feed_service::{self, Feed, Site};
+use crate::services::{crud_service, site_service};
+use crate::utils::etag;
+
+pub fn routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(rss_feed).service(atom_feed);
+}
+
+#[derive(Deserialize)]
+struct FeedQuery {
+    /// Also include certificates in the feed
+    #[serde(default)]
+    certificates: bool,
+}
+
+/// The feed's view of the request's site: links point at its domain (keeping
+/// the request's port when it was resolved from `Host`), and the feed is
+/// titled with the name on its About record.
+async fn site(req: &HttpRequest, site: &site::Site, about: &Repo<About>) -> Result<Site, APIError> {
+    let (host, url) = {
+        let info = req.connection_info();
+        let host = match &site.domain {
+            Some(domain) if site_service::normalize(info.host()) != *domain => domain.clone(),
+            _ => info.host().to_string(),
+        };
+        let url = format!("{}://{}", info.scheme(), host);
+        (host, url)
+    };
+
+    let title = crud_service::get_all(about.get_ref())
+        .await?
+        .into_iter()
+        .find(|about| site.domain.as_deref() == Some(&site_service::normalize(&about.domain)))
+        .map_or(host, |about| about.name);
+    Ok(Site { title, url })
+}
+
+async fn load(
+    site: &Site,
+    query: &FeedQuery,
+    revisions: &Repo<Revision>,
+    posts: &Repo<Post>,
+    certificates: &Repo<Certificate>,
+) -> Result<Feed, APIError> {
+    let certificates = query.certificates.then(|| certificates.get_ref());
+    feed_service::build_feed(site, revisions.get_ref(), posts.get_ref(), certificates).await
+}
+
+/// Feeds are polled by aggregators, so they are cached longer than the API.
+const FEED_CACHE: CachePolicy = CachePolicy("public, max-age=900");
+
+fn xml_response(req: &HttpRequest, content_type: &'static str, body: String, feed: &Feed) -> HttpResponse {
+    let tag = EntityTag::new_strong(etag(body.as_bytes()));
+    let modified = HttpDate::from(SystemTime::UNIX_EPOCH + Duration::from_secs(feed.updated.timestamp().max(0) as u64));
+
+    conditional(req, tag, Some(modified), FEED_CACHE, |response| {
+        response.content_type(content_type).body(body)
+    })
+}
+
+#[get("/feed.xml")]
+async fn rss_feed(
+    req: HttpRequest,
+    query: web::Query<FeedQuery>,
+    current: site::Site,
+    about: Repo<About>,
+    revisions: Repo<Revision>,
+    posts: Repo<Post>,
+    certificates: Repo<Certificate>,
+) -> Result<HttpResponse, APIError> {
+    let site = site(&req, &current, &about).await?;
+    let feed = load(&site, &query, &revisions, &posts, &certificates).await?;
+    let body = feed_service::rss(&site, &feed);
+    Ok(xml_response(&req, "application/rss+xml; charset=utf-8", body, &feed))
+}
+
+#[get("/atom.xml")]
+async fn atom_feed(
+    req: HttpRequest,
+    query: web::Query<FeedQuery>,
+    current: site::Site,
+    about: Repo<About>,
+    revisions: Repo<Revision>,
+    posts: Repo<Post>,
+    certificates: Repo<Certificate>,
+) -> Result<HttpResponse, APIError> {
+    let site = site(&req, &current, &about).await?;
+    let feed = load(&site, &query, &revisions, &posts, &certificates).await?;
+    let body = feed_service::atom(&site, &feed);
+    Ok(xml_response(&req, "application/atom+xml; charset=utf-8", body, &feed))
+}
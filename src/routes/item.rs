@@ -0,0 +1,58 @@
+use actix_web::{FromRequest, HttpRequest, Resource, dev::Payload, web};
+use futures::future::{Ready, ready};
+
+use crate::error::APIError;
+
+/// The path of a single record that sub-resources such as revisions hang
+/// off: either a path with an `{id}` segment or a singleton with a fixed id.
+#[derive(Debug, Clone)]
+pub struct ItemPath {
+    path: String,
+    singleton: Option<Singleton>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Singleton(&'static str);
+
+impl ItemPath {
+    /// `path` addresses a single record through an `{id}` segment.
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            singleton: None,
+        }
+    }
+
+    /// For a resource that always addresses the record `id`.
+    pub fn singleton(path: &str, id: &'static str) -> Self {
+        Self {
+            path: path.to_string(),
+            singleton: Some(Singleton(id)),
+        }
+    }
+
+    /// A resource at `{path}{suffix}` whose handlers can extract a [`RecordId`].
+    pub fn resource(&self, suffix: &str) -> Resource {
+        let resource = web::resource(format!("{}{}", self.path, suffix));
+        match self.singleton {
+            Some(singleton) => resource.app_data(singleton),
+            None => resource,
+        }
+    }
+}
+
+/// The id of the record an [`ItemPath`] resource is about.
+pub struct RecordId(pub String);
+
+impl FromRequest for RecordId {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let id = match req.match_info().get("id") {
+            Some(id) => Some(id.to_string()),
+            None => req.app_data::<Singleton>().map(|singleton| singleton.0.to_string()),
+        };
+        ready(id.map(RecordId).ok_or_else(|| APIError::NotFound.into()))
+    }
+}
@@ -0,0 +1,48 @@
+use std::collections::HashMap;
+
+use actix_web::http::header::{self, AcceptLanguage, HeaderValue};
+use actix_web::{FromRequest, HttpMessage, HttpRequest, HttpResponse, dev::Payload, web};
+use futures::future::{Ready, ready};
+
+use crate::models::locale::Locale;
+
+/// Negotiates the response language: `?lang=` when given, otherwise the
+/// best `Accept-Language` match, falling back to the default locale.
+impl FromRequest for Locale {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let params = web::Query::<HashMap<String, String>>::from_query(req.query_string())
+            .map(web::Query::into_inner)
+            .unwrap_or_default();
+        if let Some(lang) = params.get("lang") {
+            return ready(Locale::parse(lang).map_err(Into::into));
+        }
+
+        let preferred: Vec<String> = req
+            .get_header::<AcceptLanguage>()
+            .map(|accept| {
+                accept
+                    .ranked()
+                    .into_iter()
+                    .filter_map(|preference| preference.item().map(ToString::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+        ready(Ok(Locale::negotiate(preferred.iter().map(String::as_str))))
+    }
+}
+
+/// Marks `response` as written in `locale`: it varies by `Accept-Language`
+/// and carries a `Content-Language`.
+pub fn localized(locale: &Locale, mut response: HttpResponse) -> HttpResponse {
+    let headers = response.headers_mut();
+    headers.append(header::VARY, HeaderValue::from_static("Accept-Language"));
+    if let Locale::One(locale) = locale
+        && let Ok(value) = HeaderValue::from_str(locale)
+    {
+        headers.insert(header::CONTENT_LANGUAGE, value);
+    }
+    response
+}
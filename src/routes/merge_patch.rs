@@ -0,0 +1,32 @@
+use actix_web::{FromRequest, HttpMessage, HttpRequest, dev::Payload, web};
+use futures::future::LocalBoxFuture;
+use serde_json::Value;
+
+use crate::error::APIError;
+
+/// An RFC 7396 JSON Merge Patch body, sent as `application/merge-patch+json`
+/// (plain `application/json` is accepted too).
+pub struct MergePatch(pub Value);
+
+impl FromRequest for MergePatch {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let content_type = req.content_type().to_string();
+        let body = web::Bytes::from_request(req, payload);
+
+        Box::pin(async move {
+            if content_type != "application/merge-patch+json" && content_type != "application/json" {
+                return Err(APIError::Validation(
+                    "expected Content-Type: application/merge-patch+json".to_string(),
+                )
+                .into());
+            }
+
+            let body = body.await?;
+            let patch = serde_json::from_slice(&body).map_err(|e| APIError::Validation(e.to_string()))?;
+            Ok(MergePatch(patch))
+        })
+    }
+}
@@ -1,7 +1,18 @@
+pub mod conditional;
+pub mod crud;
+pub mod feed;
+pub mod item;
+pub mod locale;
+pub mod merge_patch;
+pub mod publishing;
+pub mod record_body;
+pub mod revisions;
+pub mod site;
 pub mod v1;
 
 use actix_web::web;
 
 pub fn config(cfg: &mut web::ServiceConfig) {
-    cfg.service(web::scope("/v1").configure(v1::config));
+    cfg.service(web::scope("/v1").configure(v1::config))
+        .configure(feed::routes);
 }
@@ -0,0 +1,82 @@
+use std::marker::PhantomData;
+
+use actix_web::{HttpResponse, Result, web};
+
+use crate::auth::preview;
+use crate::auth::user::AuthenticatedUser;
+use crate::error::APIError;
+use crate::models::Record;
+use crate::models::locale::Locale;
+use crate::models::revision::Action;
+use crate::models::status::Status;
+use crate::repository::Repository;
+use crate::routes::site::Repo;
+use crate::routes::conditional::etag_in;
+use crate::routes::item::{ItemPath, RecordId};
+use crate::routes::locale::localized;
+use crate::services::crud_service;
+
+/// Registers `POST {item}/publish` and `POST {item}/unpublish`, which move a
+/// record to [`Status::Published`] and back to [`Status::Draft`] and are
+/// recorded as revisions, and `POST {item}/preview`, which mints a
+/// short-lived token that lets anyone read the record with `?preview=<token>`.
+/// All of them require an [`AuthenticatedUser`].
+pub struct PublishRoutes<T> {
+    item: ItemPath,
+    _model: PhantomData<fn() -> T>,
+}
+
+impl<T: Record> PublishRoutes<T> {
+    pub fn at(item: ItemPath) -> Self {
+        Self {
+            item,
+            _model: PhantomData,
+        }
+    }
+
+    pub fn register(self, cfg: &mut web::ServiceConfig) {
+        cfg.service(self.item.resource("/publish").route(web::post().to(publish::<T>)))
+            .service(self.item.resource("/unpublish").route(web::post().to(unpublish::<T>)))
+            .service(self.item.resource("/preview").route(web::post().to(mint_preview::<T>)));
+    }
+}
+
+async fn publish<T: Record>(
+    user: AuthenticatedUser,
+    locale: Locale,
+    repo: Repo<T>,
+    RecordId(id): RecordId,
+) -> Result<HttpResponse, APIError> {
+    transition(user, &locale, repo.get_ref(), &id, Status::Published, Action::Publish).await
+}
+
+async fn unpublish<T: Record>(
+    user: AuthenticatedUser,
+    locale: Locale,
+    repo: Repo<T>,
+    RecordId(id): RecordId,
+) -> Result<HttpResponse, APIError> {
+    transition(user, &locale, repo.get_ref(), &id, Status::Draft, Action::Unpublish).await
+}
+
+async fn mint_preview<T: Record>(
+    _user: AuthenticatedUser,
+    repo: Repo<T>,
+    RecordId(id): RecordId,
+) -> Result<HttpResponse, APIError> {
+    crud_service::get(repo.get_ref(), &id).await?;
+    let token = preview::mint(repo.table(), &id, preview::ttl())?;
+    Ok(HttpResponse::Created().json(token))
+}
+
+async fn transition<T: Record>(
+    user: AuthenticatedUser,
+    locale: &Locale,
+    repo: &dyn Repository<T>,
+    id: &str,
+    status: Status,
+    action: Action,
+) -> Result<HttpResponse, APIError> {
+    let item = crud_service::set_status(repo, id, status, &user.0.email, action).await?;
+    Ok(localized(locale, HttpResponse::Ok().insert_header(etag_in(&item, locale)).json(locale.record(&item))))
+}
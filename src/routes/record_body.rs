@@ -0,0 +1,47 @@
+use actix_web::{FromRequest, HttpRequest, dev::Payload, web};
+use futures::future::LocalBoxFuture;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::error::APIError;
+use crate::models::status::Status;
+
+/// The JSON body of a create request. A record sent without a `status` is
+/// a draft; only rows stored before statuses existed default to published.
+pub struct RecordBody<T>(pub T);
+
+impl<T: DeserializeOwned + 'static> FromRequest for RecordBody<T> {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let body = web::Json::<Value>::from_request(req, payload);
+
+        Box::pin(async move {
+            let mut body = body.await?.into_inner();
+            if let Some(fields) = body.as_object_mut() {
+                fields
+                    .entry("status")
+                    .or_insert_with(|| serde_json::json!(Status::Draft));
+            }
+            let record = serde_json::from_value(body).map_err(|e| APIError::Validation(e.to_string()))?;
+            Ok(RecordBody(record))
+        })
+    }
+}
+
+/// The JSON body of a replace request, read into a record by
+/// [`crud_service::replace`](crate::services::crud_service::replace) once
+/// the fields it leaves out are filled in from the current record.
+pub struct Replacement(pub Value);
+
+impl FromRequest for Replacement {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let body = web::Json::<Value>::from_request(req, payload);
+
+        Box::pin(async move { Ok(Replacement(body.await?.into_inner())) })
+    }
+}
@@ -0,0 +1,90 @@
+use std::marker::PhantomData;
+
+use actix_web::{HttpResponse, Result, web};
+use serde::Deserialize;
+
+use crate::auth::user::AuthenticatedUser;
+use crate::error::APIError;
+use crate::models::Record;
+use crate::models::locale::Locale;
+use crate::routes::site::Repo;
+use crate::routes::conditional::etag_in;
+use crate::routes::item::{ItemPath, RecordId};
+use crate::routes::locale::localized;
+use crate::services::revision_service;
+
+/// Registers the revision history endpoints of a record, all of which
+/// require an [`AuthenticatedUser`]. The history is read from the
+/// [revisions](crate::repository::Tables::revisions) of `T`:
+///
+/// - `GET {item}/revisions`, newest first
+/// - `GET {item}/revisions/diff?from={rev}&to={rev}`, a JSON Merge Patch from
+///   one revision to another, or to the current record when `to` is omitted
+/// - `POST {item}/revisions/{rev}/restore`
+///
+/// ```ignore
+/// RevisionRoutes::<Project>::at(ItemPath::new("/projects/{id}")).register(cfg);
+/// RevisionRoutes::<Contact>::at(ItemPath::singleton("/contact", "default")).register(cfg);
+/// ```
+pub struct RevisionRoutes<T> {
+    item: ItemPath,
+    _model: PhantomData<fn() -> T>,
+}
+
+impl<T: Record> RevisionRoutes<T> {
+    pub fn at(item: ItemPath) -> Self {
+        Self {
+            item,
+            _model: PhantomData,
+        }
+    }
+
+    pub fn register(self, cfg: &mut web::ServiceConfig) {
+        cfg.service(self.item.resource("/revisions").route(web::get().to(list::<T>)))
+            .service(self.item.resource("/revisions/diff").route(web::get().to(diff::<T>)))
+            .service(self.item.resource("/revisions/{rev}/restore").route(web::post().to(restore::<T>)));
+    }
+}
+
+#[derive(Deserialize)]
+struct RevisionPath {
+    rev: String,
+}
+
+#[derive(Deserialize)]
+struct DiffQuery {
+    from: String,
+    to: Option<String>,
+}
+
+async fn list<T: Record>(
+    _user: AuthenticatedUser,
+    repo: Repo<T>,
+    RecordId(id): RecordId,
+) -> Result<HttpResponse, APIError> {
+    let history = revision_service::history::<T>(repo.tables().revisions::<T>().as_ref(), &id).await?;
+    Ok(HttpResponse::Ok().json(history))
+}
+
+async fn diff<T: Record>(
+    _user: AuthenticatedUser,
+    repo: Repo<T>,
+    RecordId(id): RecordId,
+    query: web::Query<DiffQuery>,
+) -> Result<HttpResponse, APIError> {
+    let revisions = repo.tables().revisions::<T>();
+    let patch = revision_service::diff_revisions(revisions.as_ref(), repo.get_ref(), &id, &query.from, query.to.as_deref()).await?;
+    Ok(HttpResponse::Ok().content_type("application/merge-patch+json").json(patch))
+}
+
+async fn restore<T: Record>(
+    user: AuthenticatedUser,
+    locale: Locale,
+    repo: Repo<T>,
+    RecordId(id): RecordId,
+    path: web::Path<RevisionPath>,
+) -> Result<HttpResponse, APIError> {
+    let revisions = repo.tables().revisions::<T>();
+    let item = revision_service::restore(revisions.as_ref(), repo.get_ref(), &user.0.email, &id, &path.rev).await?;
+    Ok(localized(&locale, HttpResponse::Ok().insert_header(etag_in(&item, &locale)).json(locale.record(&item))))
+}
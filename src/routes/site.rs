@@ -0,0 +1,80 @@
+use std::ops::Deref;
+use std::sync::Arc;
+
+use actix_web::{FromRequest, HttpMessage, HttpRequest, dev::Payload, web};
+use futures::future::LocalBoxFuture;
+
+use crate::error::APIError;
+use crate::models::Record;
+use crate::models::about::About;
+use crate::models::site::Site;
+use crate::repository::{Repository, Store};
+use crate::services::site_service;
+
+/// Picks the site explicitly, e.g. when one admin manages several sites.
+/// Without it the site is resolved from the `Host` header.
+pub const SITE_HEADER: &str = "X-Site";
+
+impl FromRequest for Site {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let req = req.clone();
+        Box::pin(async move {
+            if let Some(site) = req.extensions().get::<Site>() {
+                return Ok(site.clone());
+            }
+
+            let store = req
+                .app_data::<web::Data<Store>>()
+                .cloned()
+                .ok_or(APIError::InternalServerError)?;
+            let explicit = req
+                .headers()
+                .get(SITE_HEADER)
+                .and_then(|header| header.to_str().ok())
+                .map(str::to_string);
+            let domain = explicit
+                .clone()
+                .unwrap_or_else(|| req.connection_info().host().to_string());
+
+            let about = store.repository::<About>();
+            let site = site_service::resolve(about.as_ref(), Some(&domain), explicit.is_some()).await?;
+            req.extensions_mut().insert(site.clone());
+            Ok(site)
+        })
+    }
+}
+
+/// The repository of `T` for the request's [`Site`].
+pub struct Repo<T: Record>(Arc<dyn Repository<T>>);
+
+impl<T: Record> Repo<T> {
+    pub fn get_ref(&self) -> &dyn Repository<T> {
+        self.0.as_ref()
+    }
+}
+
+impl<T: Record> Deref for Repo<T> {
+    type Target = dyn Repository<T>;
+
+    fn deref(&self) -> &Self::Target {
+        self.0.as_ref()
+    }
+}
+
+impl<T: Record> FromRequest for Repo<T> {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let store = req.app_data::<web::Data<Store>>().cloned();
+        let site = Site::from_request(req, payload);
+        Box::pin(async move {
+            let store = store.ok_or(APIError::InternalServerError)?;
+            let site = site.await?;
+            Ok(Repo(store.repository_for::<T>(&site)))
+        })
+    }
+}
@@ -1,245 +1,101 @@
 use crate::{error::APIError, models::about::Education, auth::user::AuthenticatedUser};
+use crate::auth::preview::Preview;
 use crate::models::about::{About, Certificate, Contact, ProgLanguage, SpokenLanguage, TechStack};
+use crate::models::locale::Locale;
+use crate::models::site::Site;
+use crate::models::status::Viewer;
+use crate::routes::site::Repo;
+use crate::routes::crud::CrudResource;
+use crate::routes::item::ItemPath;
+use crate::routes::locale::localized;
+use crate::routes::publishing::PublishRoutes;
+use crate::routes::revisions::RevisionRoutes;
 use crate::services::about_service;
-use actix_web::{HttpResponse, Result, delete, get, post, put, web};
+use crate::routes::conditional::{CachePolicy, Precondition, cached_json, cached_record, etag};
+use crate::routes::merge_patch::MergePatch;
+use crate::routes::record_body::{RecordBody, Replacement};
+use actix_web::{HttpRequest, HttpResponse, Result, delete, get, patch, post, put, web};
 
 pub fn routes(cfg: &mut web::ServiceConfig) {
-    cfg.service(get_all_about)
-        .service(get_about)
-        .service(get_all_education)
-        .service(get_education)
+    cfg.service(get_current_about)
         .service(get_contact)
-        .service(get_all_certificates)
-        .service(get_certificate)
-        .service(get_programming_languages)
-        .service(get_spoken_languages)
-        .service(get_tech_stacks)
-        .service(create_about)
-        .service(create_education)
         .service(create_contact)
-        .service(create_certificate)
-        .service(create_programming_language)
-        .service(create_spoken_language)
-        .service(create_tech_stack)
-        .service(put_about)
-        .service(put_education)
         .service(put_contact)
-        .service(put_certificate)
-        .service(put_programming_language)
-        .service(put_spoken_language)
-        .service(put_tech_stack)
-        .service(delete_about)
-        .service(delete_education)
-        .service(delete_contact)
-        .service(delete_certificate)
-        .service(delete_programming_language)
-        .service(delete_spoken_language)
-        .service(delete_tech_stack);
-}
-
-
-#[get("/about/all")]
-async fn get_all_about() -> Result<HttpResponse, APIError> {
-    let abouts = about_service::get_all_about().await?;
-    Ok(HttpResponse::Ok().json(abouts))
-}
-
-#[get("/about/{id}")]
-async fn get_about(id: web::Path<String>) -> Result<HttpResponse, APIError> {
-    let about = about_service::get_about(&id).await?;
-    Ok(HttpResponse::Ok().json(about))
-}
-
-#[post("/about")]
-async fn create_about(_user: AuthenticatedUser, payload: web::Json<About>) -> Result<HttpResponse, APIError> {
-    let about = about_service::create_about(payload.into_inner()).await?;
-    Ok(HttpResponse::Created().json(about))
-}
-
-#[put("/about/{id}")]
-async fn put_about(_user: AuthenticatedUser, id: web::Path<String>, payload: web::Json<About>) -> Result<HttpResponse, APIError> {
-    let updated_about = about_service::update_about( &id, payload.into_inner()).await?;
-    Ok(HttpResponse::Ok().json(updated_about))
-}
-
-#[delete("/about/{id}")]
-async fn delete_about(_user: AuthenticatedUser, id: web::Path<String>) -> Result<HttpResponse, APIError> {
-    about_service::delete_about(&id).await?;
-    Ok(HttpResponse::NoContent().finish())
-}
-
-#[get("/education/all")]
-async fn get_all_education() -> Result<HttpResponse, APIError> {
-    let educations = about_service::get_all_education().await?;
-    Ok(HttpResponse::Ok().json(educations))
-}
-
-#[post("/education")]
-async fn create_education(_user: AuthenticatedUser, payload: web::Json<Education>) -> Result<HttpResponse, APIError> {
-    let education = about_service::create_education(payload.into_inner()).await?;
-    Ok(HttpResponse::Created().json(education))
-}
-
-#[get("/education/{id}")]
-async fn get_education(id: web::Path<String>) -> Result<HttpResponse, APIError> {
-    let education = about_service::get_education(&id).await?;
-    Ok(HttpResponse::Ok().json(education))
-}
-
-#[put("/education/{id}")]
-async fn put_education(
-    _user: AuthenticatedUser,
-    id: web::Path<String>,
-    payload: web::Json<Education>,
-) -> Result<HttpResponse, APIError> {
-    let updated_education = about_service::update_education(&id, payload.into_inner()).await?;
-    Ok(HttpResponse::Ok().json(updated_education))
-}
-
-#[delete("/education/{id}")]
-async fn delete_education(_user: AuthenticatedUser, id: web::Path<String>) -> Result<HttpResponse, APIError> {
-    about_service::delete_education(&id).await?;
-    Ok(HttpResponse::NoContent().finish())
+        .service(patch_contact)
+        .service(delete_contact);
+
+    PublishRoutes::<Contact>::at(ItemPath::singleton("/contact", "default")).register(cfg);
+    RevisionRoutes::<Contact>::at(ItemPath::singleton("/contact", "default")).register(cfg);
+
+    CrudResource::<About>::new("/about")
+        .list_at("/all")
+        .cache_control(CachePolicy::PROFILE)
+        .register(cfg);
+    CrudResource::<Education>::new("/education")
+        .list_at("/all")
+        .orderable()
+        .cache_control(CachePolicy::PROFILE)
+        .register(cfg);
+    CrudResource::<Certificate>::new("/certificates")
+        .list_at("/all")
+        .orderable()
+        .cache_control(CachePolicy::PROFILE)
+        .register(cfg);
+    CrudResource::<ProgLanguage>::new("/programming-languages")
+        .orderable()
+        .cache_control(CachePolicy::PROFILE)
+        .register(cfg);
+    CrudResource::<SpokenLanguage>::new("/spoken-languages")
+        .orderable()
+        .cache_control(CachePolicy::PROFILE)
+        .register(cfg);
+    CrudResource::<TechStack>::new("/tech-stacks")
+        .orderable()
+        .cache_control(CachePolicy::PROFILE)
+        .register(cfg);
+}
+
+#[get("/about")]
+async fn get_current_about(req: HttpRequest, viewer: Viewer, locale: Locale, site: Site, repo: Repo<About>) -> Result<HttpResponse, APIError> {
+    let about = about_service::get_current_about(repo.get_ref(), &site, viewer).await?;
+    let body = locale.record(&about);
+    Ok(localized(&locale, cached_record(&req, &about, &locale, &body, CachePolicy::PROFILE.for_viewer(viewer))))
 }
 
 #[get("/contact")]
-async fn get_contact() -> Result<HttpResponse, APIError> {
-    let contact = about_service::get_contact().await?;
-    Ok(HttpResponse::Ok().json(contact))
+async fn get_contact(req: HttpRequest, viewer: Viewer, preview: Preview, repo: Repo<Contact>) -> Result<HttpResponse, APIError> {
+    let viewer = preview.viewer_for(viewer, repo.table(), "default");
+    let contact = about_service::get_contact(repo.get_ref(), viewer).await?;
+    let policy = CachePolicy::PROFILE.for_viewer(viewer);
+    Ok(match contact.first() {
+        Some(current) => cached_record(&req, current, &Locale::All, &contact, policy),
+        None => cached_json(&req, &contact, policy),
+    })
 }
 
 #[post("/contact")]
-async fn create_contact(_user: AuthenticatedUser, payload: web::Json<Contact>) -> Result<HttpResponse, APIError> {
-    let contact = about_service::create_contact(payload.into_inner()).await?;
-    Ok(HttpResponse::Created().json(contact))
+async fn create_contact(user: AuthenticatedUser, repo: Repo<Contact>, RecordBody(payload): RecordBody<Contact>) -> Result<HttpResponse, APIError> {
+    let contact = about_service::create_contact(repo.get_ref(), payload, &user.0.email).await?;
+    Ok(HttpResponse::Created().insert_header(etag(&contact)).json(contact))
 }
 
 #[put("/contact")]
-async fn put_contact(_user: AuthenticatedUser, payload: web::Json<Contact>) -> Result<HttpResponse, APIError> {
-    let updated_contact = about_service::update_contact(payload.into_inner()).await?;
-    Ok(HttpResponse::Ok().json(updated_contact))
-}
-
-#[delete("/contact")]
-async fn delete_contact(_user: AuthenticatedUser) -> Result<HttpResponse, APIError> {
-    about_service::delete_contact().await?;
-    Ok(HttpResponse::NoContent().finish())
-}
-
-#[get("/certificates/all")]
-async fn get_all_certificates() -> Result<HttpResponse, APIError> {
-    let certificates = about_service::get_all_certificates().await?;
-    Ok(HttpResponse::Ok().json(certificates))
-}
-
-#[get("/certificates/{id}")]
-async fn get_certificate(id: web::Path<String>) -> Result<HttpResponse, APIError> {
-    let certificate = about_service::get_certificate(&id).await?;
-    Ok(HttpResponse::Ok().json(certificate))
+async fn put_contact(user: AuthenticatedUser, repo: Repo<Contact>, precondition: Precondition, Replacement(payload): Replacement) -> Result<HttpResponse, APIError> {
+    let expected = precondition.verify(repo.get_ref(), "default").await?;
+    let updated_contact = about_service::update_contact(repo.get_ref(), payload, &user.0.email, expected.as_ref()).await?;
+    Ok(HttpResponse::Ok().insert_header(etag(&updated_contact)).json(updated_contact))
 }
 
-#[post("/certificates")]
-async fn create_certificate(_user: AuthenticatedUser, payload: web::Json<Certificate>) -> Result<HttpResponse, APIError> {
-    let certificate = about_service::create_certificate(payload.into_inner()).await?;
-    Ok(HttpResponse::Created().json(certificate))
+#[patch("/contact")]
+async fn patch_contact(user: AuthenticatedUser, repo: Repo<Contact>, precondition: Precondition, MergePatch(patch): MergePatch) -> Result<HttpResponse, APIError> {
+    let expected = precondition.verify(repo.get_ref(), "default").await?;
+    let patched_contact = about_service::patch_contact(repo.get_ref(), patch, &user.0.email, expected.as_ref()).await?;
+    Ok(HttpResponse::Ok().insert_header(etag(&patched_contact)).json(patched_contact))
 }
 
-#[put("/certificates/{id}")]
-async fn put_certificate(
-    _user: AuthenticatedUser,
-    id: web::Path<String>,
-    payload: web::Json<Certificate>,
-) -> Result<HttpResponse, APIError> {
-    let updated_certificate = about_service::update_certificate(&id, payload.into_inner()).await?;
-    Ok(HttpResponse::Ok().json(updated_certificate))
-}
-
-#[delete("/certificates/{id}")]
-async fn delete_certificate(_user: AuthenticatedUser, id: web::Path<String>) -> Result<HttpResponse, APIError> {
-    about_service::delete_certificate(&id).await?;
-    Ok(HttpResponse::NoContent().finish())
-}
-
-#[get("/programming-languages")]
-async fn get_programming_languages() -> Result<HttpResponse, APIError> {
-    let languages = about_service::get_programming_languages().await?;
-    Ok(HttpResponse::Ok().json(languages))
-}
-
-#[post("/programming-languages")]
-async fn create_programming_language(_user: AuthenticatedUser, payload: web::Json<ProgLanguage>) -> Result<HttpResponse, APIError> {
-    let language = about_service::create_programming_language(payload.into_inner()).await?;
-    Ok(HttpResponse::Created().json(language))
-}
-
-#[put("/programming-languages/{id}")]
-async fn put_programming_language(
-    _user: AuthenticatedUser,
-    id: web::Path<String>,
-    payload: web::Json<ProgLanguage>,
-) -> Result<HttpResponse, APIError> {
-    let updated_language = about_service::update_programming_language(&id, payload.into_inner()).await?;
-    Ok(HttpResponse::Ok().json(updated_language))
-}
-
-#[delete("/programming-languages/{id}")]
-async fn delete_programming_language(_user: AuthenticatedUser, id: web::Path<String>) -> Result<HttpResponse, APIError> {
-    about_service::delete_programming_language(&id).await?;
-    Ok(HttpResponse::NoContent().finish())
-}
-
-#[get("/spoken-languages")]
-async fn get_spoken_languages() -> Result<HttpResponse, APIError> {
-    let languages = about_service::get_spoken_languages().await?;
-    Ok(HttpResponse::Ok().json(languages))
-}
-
-#[post("/spoken-languages")]
-async fn create_spoken_language(_user: AuthenticatedUser, payload: web::Json<SpokenLanguage>) -> Result<HttpResponse, APIError> {
-    let language = about_service::create_spoken_language(payload.into_inner()).await?;
-    Ok(HttpResponse::Created().json(language))
-}
-
-#[put("/spoken-languages/{id}")]
-async fn put_spoken_language(
-    _user: AuthenticatedUser,
-    id: web::Path<String>,
-    payload: web::Json<SpokenLanguage>,
-) -> Result<HttpResponse, APIError> {
-    let updated_language = about_service::update_spoken_language(&id, payload.into_inner()).await?;
-    Ok(HttpResponse::Ok().json(updated_language))
-}
-
-#[delete("/spoken-languages/{id}")]
-async fn delete_spoken_language(_user: AuthenticatedUser, id: web::Path<String>) -> Result<HttpResponse, APIError> {
-    about_service::delete_spoken_language(&id).await?;
-    Ok(HttpResponse::NoContent().finish())
-}
-
-#[get("/tech-stacks")]
-async fn get_tech_stacks() -> Result<HttpResponse, APIError> {
-    let tech_stacks = about_service::get_tech_stacks().await?;
-    Ok(HttpResponse::Ok().json(tech_stacks))
-}
-
-#[post("/tech-stacks")]
-async fn create_tech_stack(_user: AuthenticatedUser, payload: web::Json<TechStack>) -> Result<HttpResponse, APIError> {
-    let tech_stack = about_service::create_tech_stack(payload.into_inner()).await?;
-    Ok(HttpResponse::Created().json(tech_stack))
-}
-
-#[put("/tech-stacks/{id}")]
-async fn put_tech_stack(
-    _user: AuthenticatedUser,
-    id: web::Path<String>,
-    payload: web::Json<TechStack>,
-) -> Result<HttpResponse, APIError> {
-    let updated_tech_stack = about_service::update_tech_stack(&id, payload.into_inner()).await?;
-    Ok(HttpResponse::Ok().json(updated_tech_stack))
-}
-
-#[delete("/tech-stacks/{id}")]
-async fn delete_tech_stack(_user: AuthenticatedUser, id: web::Path<String>) -> Result<HttpResponse, APIError> {
-    about_service::delete_tech_stack(&id).await?;
+#[delete("/contact")]
+async fn delete_contact(user: AuthenticatedUser, repo: Repo<Contact>, precondition: Precondition) -> Result<HttpResponse, APIError> {
+    let expected = precondition.verify(repo.get_ref(), "default").await?;
+    about_service::delete_contact(repo.get_ref(), &user.0.email, expected.as_ref()).await?;
     Ok(HttpResponse::NoContent().finish())
 }
@@ -0,0 +1,11 @@
+use actix_web::web;
+
+use crate::models::experience::Experience;
+use crate::routes::conditional::CachePolicy;
+use crate::routes::crud::CrudResource;
+
+pub fn routes(cfg: &mut web::ServiceConfig) {
+    CrudResource::<Experience>::new("/experience")
+        .cache_control(CachePolicy::PROFILE)
+        .register(cfg);
+}
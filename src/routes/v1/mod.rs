@@ -1,9 +1,24 @@
 pub mod about;
+pub mod experience;
+pub mod posts;
+pub mod projects;
+pub mod sessions;
+pub mod sites;
+pub mod translations;
+pub mod trash;
 
 use actix_web::web;
 
-use crate::auth::google;
+use crate::auth::oauth;
 
 pub fn config(cfg: &mut web::ServiceConfig) {
-    cfg.configure(google::routes).configure(about::routes);
+    cfg.configure(oauth::routes)
+        .configure(about::routes)
+        .configure(experience::routes)
+        .configure(projects::routes)
+        .configure(posts::routes)
+        .configure(sessions::routes)
+        .configure(sites::routes)
+        .configure(translations::routes)
+        .configure(trash::routes);
 }
@@ -0,0 +1,23 @@
+use actix_web::web;
+use serde_json::{Value, json};
+
+use crate::models::post::{Post, PostSummary};
+use crate::routes::crud::CrudResource;
+use crate::utils::render_markdown;
+
+pub fn routes(cfg: &mut web::ServiceConfig) {
+    CrudResource::<Post>::new("/posts")
+        .render(with_html)
+        .summarize(summary)
+        .register(cfg);
+}
+
+/// The post together with its body rendered to sanitized HTML.
+fn with_html(post: &Post, mut body: Value) -> Value {
+    body["html"] = json!(render_markdown(&post.body));
+    body
+}
+
+fn summary(post: &Post, _: Value) -> Value {
+    json!(PostSummary::from(post.clone()))
+}
@@ -0,0 +1,12 @@
+use actix_web::web;
+
+use crate::models::project::Project;
+use crate::routes::conditional::CachePolicy;
+use crate::routes::crud::CrudResource;
+
+pub fn routes(cfg: &mut web::ServiceConfig) {
+    CrudResource::<Project>::new("/projects")
+        .orderable()
+        .cache_control(CachePolicy::PROFILE)
+        .register(cfg);
+}
@@ -0,0 +1,87 @@
+use actix_web::{HttpRequest, HttpResponse, HttpResponseBuilder, Result, delete, post, web};
+use serde::{Deserialize, Serialize};
+
+use crate::auth::session::{self, REFRESH_COOKIE};
+use crate::auth::user::AuthenticatedUser;
+use crate::error::APIError;
+use crate::repository::Store;
+use crate::services::session_service;
+
+pub fn routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(refresh).service(log_out).service(log_out_everywhere);
+}
+
+#[derive(Deserialize)]
+struct RefreshRequest {
+    refresh_token: String,
+}
+
+#[derive(Serialize)]
+struct Revoked {
+    revoked: usize,
+}
+
+/// Expires the session cookies, in case the session was kept in them.
+fn cleared(mut response: HttpResponseBuilder) -> HttpResponseBuilder {
+    for cookie in session::clear_cookies() {
+        response.cookie(cookie);
+    }
+    response
+}
+
+/// Trades a refresh token for a new access token and refresh token. Without
+/// a body, the refresh token is read from its cookie and the new tokens are
+/// set as cookies, which needs the session's CSRF token like any other
+/// cookie-authenticated write.
+#[post("/auth/refresh")]
+async fn refresh(
+    req: HttpRequest,
+    store: web::Data<Store>,
+    payload: Option<web::Json<RefreshRequest>>,
+) -> Result<HttpResponse, APIError> {
+    if let Some(payload) = payload {
+        let tokens = session_service::refresh(&store, &payload.refresh_token).await?;
+        return Ok(HttpResponse::Ok().json(tokens));
+    }
+
+    let cookie = req.cookie(REFRESH_COOKIE).ok_or(APIError::Unauthorized)?;
+    let (sid, _) = session_service::split(cookie.value())?;
+    session::verify_csrf(&req, sid)?;
+
+    let tokens = session_service::refresh(&store, cookie.value()).await?;
+    let mut response = HttpResponse::NoContent();
+    for cookie in session::session_cookies(&tokens)? {
+        response.cookie(cookie);
+    }
+    Ok(response.finish())
+}
+
+/// Ends the session of the access token or, once that expired, of the
+/// refresh token in the body or in its cookie. Like a refresh, the cookie
+/// needs the session's CSRF token.
+#[post("/auth/logout")]
+async fn log_out(
+    req: HttpRequest,
+    user: Option<AuthenticatedUser>,
+    store: web::Data<Store>,
+    payload: Option<web::Json<RefreshRequest>>,
+) -> Result<HttpResponse, APIError> {
+    if let Some(user) = user {
+        session_service::log_out(&store, &user.0).await?;
+    } else if let Some(payload) = payload {
+        session_service::log_out_with_refresh_token(&store, &payload.refresh_token).await?;
+    } else {
+        let cookie = req.cookie(REFRESH_COOKIE).ok_or(APIError::Unauthorized)?;
+        let (sid, _) = session_service::split(cookie.value())?;
+        session::verify_csrf(&req, sid)?;
+        session_service::log_out_with_refresh_token(&store, cookie.value()).await?;
+    }
+    Ok(cleared(HttpResponse::NoContent()).finish())
+}
+
+/// Ends every session of the admin, e.g. after a device was lost.
+#[delete("/auth/sessions")]
+async fn log_out_everywhere(user: AuthenticatedUser, store: web::Data<Store>) -> Result<HttpResponse, APIError> {
+    let revoked = session_service::log_out_everywhere(&store, &user.0).await?;
+    Ok(cleared(HttpResponse::Ok()).json(Revoked { revoked }))
+}
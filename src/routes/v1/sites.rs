@@ -0,0 +1,18 @@
+use actix_web::{HttpResponse, Result, get, web};
+
+use crate::auth::user::AuthenticatedUser;
+use crate::error::APIError;
+use crate::models::about::About;
+use crate::routes::site::Repo;
+use crate::services::site_service;
+
+pub fn routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_sites);
+}
+
+/// Every site the admin can manage, each selectable with `X-Site`.
+#[get("/sites")]
+async fn get_sites(_user: AuthenticatedUser, about: Repo<About>) -> Result<HttpResponse, APIError> {
+    let sites = site_service::all(about.get_ref()).await?;
+    Ok(HttpResponse::Ok().json(sites))
+}
@@ -0,0 +1,35 @@
+use actix_web::{HttpResponse, Result, get, web};
+use serde::Deserialize;
+
+use crate::auth::user::AuthenticatedUser;
+use crate::error::APIError;
+use crate::models::locale::Locale;
+use crate::models::site::Site;
+use crate::repository::Store;
+use crate::services::translation_service;
+
+pub fn routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_missing_translations);
+}
+
+#[derive(Deserialize)]
+struct MissingQuery {
+    lang: Option<String>,
+}
+
+/// The localized fields of the site that still need translating,
+/// optionally only into `?lang=`.
+#[get("/translations/missing")]
+async fn get_missing_translations(
+    _user: AuthenticatedUser,
+    store: web::Data<Store>,
+    site: Site,
+    query: web::Query<MissingQuery>,
+) -> Result<HttpResponse, APIError> {
+    let locale = match query.lang.as_deref().map(Locale::parse).transpose()? {
+        Some(Locale::One(locale)) => Some(locale),
+        Some(Locale::All) | None => None,
+    };
+    let items = translation_service::missing(&store.translations(&site), locale.as_deref()).await?;
+    Ok(HttpResponse::Ok().json(items))
+}
@@ -0,0 +1,29 @@
+use actix_web::{HttpResponse, Result, get, post, web};
+
+use crate::auth::user::AuthenticatedUser;
+use crate::error::APIError;
+use crate::models::site::Site;
+use crate::repository::Store;
+use crate::services::trash_service;
+
+pub fn routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_trash).service(restore_from_trash);
+}
+
+#[get("/trash")]
+async fn get_trash(_user: AuthenticatedUser, store: web::Data<Store>, site: Site) -> Result<HttpResponse, APIError> {
+    let items = trash_service::list(&store.trash(&site)).await?;
+    Ok(HttpResponse::Ok().json(items))
+}
+
+#[post("/trash/{collection}/{id}/restore")]
+async fn restore_from_trash(
+    user: AuthenticatedUser,
+    store: web::Data<Store>,
+    site: Site,
+    path: web::Path<(String, String)>,
+) -> Result<HttpResponse, APIError> {
+    let (collection, id) = path.into_inner();
+    let item = trash_service::restore(&store.trash(&site), &user.0.email, &collection, &id).await?;
+    Ok(HttpResponse::Ok().json(item))
+}
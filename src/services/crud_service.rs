@@ -0,0 +1,243 @@
+use std::collections::HashSet;
+
+use chrono::Utc;
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+use crate::models::{Record, document, locale, patched};
+use crate::models::listing::{ListQuery, Page};
+use crate::models::revision::Action;
+use crate::models::status::{Status, Viewer};
+use crate::repository::{References, Stamp, Write};
+use crate::services::revision_service::revision;
+use crate::utils::{diff, merge_patch};
+use crate::services::prelude::*;
+
+/// Every record that is not in the trash, in the model's default order.
+pub async fn get_all<T: Record>(repo: &dyn Repository<T>) -> Result<Vec<T>, APIError> {
+    let mut items = repo.list().await?;
+    items.retain(|item| item.deleted_at().is_none());
+    T::sort(&mut items);
+    Ok(items)
+}
+
+/// A page of the records `viewer` can see.
+pub async fn list<T: Record>(repo: &dyn Repository<T>, query: &ListQuery, viewer: Viewer) -> Result<Page<T>, APIError> {
+    let query = ListQuery {
+        published_only: viewer == Viewer::Public,
+        ..query.clone()
+    };
+    repo.page(&query).await
+}
+
+pub async fn get<T: Record>(repo: &dyn Repository<T>, id: &str) -> Result<T, APIError> {
+    let result = repo.get(id).await?;
+    result.filter(|item| item.deleted_at().is_none()).ok_or(APIError::NotFound)
+}
+
+fn check_schedule<T: Record>(item: &T) -> Result<(), APIError> {
+    if let (Some(publish_at), Some(unpublish_at)) = (item.publish_at(), item.unpublish_at())
+        && unpublish_at <= publish_at
+    {
+        return Err(APIError::Validation("unpublish_at must be after publish_at".to_string()));
+    }
+    Ok(())
+}
+
+/// Checks that the records `item` points at exist in `repo`'s site.
+async fn check_references<T: Record>(repo: &dyn Repository<T>, item: &T) -> Result<(), APIError> {
+    let mut references = References::new(repo.tables());
+    item.references(&mut references);
+    references.check().await
+}
+
+/// Like [`get`], but 404 when `viewer` cannot see the record.
+pub async fn view<T: Record>(repo: &dyn Repository<T>, id: &str, viewer: Viewer) -> Result<T, APIError> {
+    let item = get(repo, id).await?;
+    if !viewer.can_see(&item) {
+        return Err(APIError::NotFound);
+    }
+    Ok(item)
+}
+
+/// Creates a record, stamping its [`created_at`](Record::created_at) and
+/// giving it a random id unless it has one. An id that is taken, even by a
+/// trashed record, is a conflict. Every write of this module is recorded
+/// as a revision by `author`.
+pub async fn create<T: Record>(repo: &dyn Repository<T>, data: T, author: &str) -> Result<T, APIError> {
+    create_as(repo, data, author, Action::Create).await
+}
+
+/// [`create`], recorded as `action`.
+pub async fn create_as<T: Record>(repo: &dyn Repository<T>, mut data: T, author: &str, action: Action) -> Result<T, APIError> {
+    data.prepare();
+    data.validate()?;
+    check_schedule(&data)?;
+    locale::check(&data)?;
+    check_references(repo, &data).await?;
+    if data.created_at().is_none() {
+        data.set_created_at(Utc::now());
+    }
+    let id = match data.id() {
+        Some(id) => id.to_string(),
+        None => Uuid::new_v4().simple().to_string(),
+    };
+    data.set_id(id.clone());
+    if repo.get(&id).await?.is_some_and(|existing| existing.deleted_at().is_some()) {
+        return Err(APIError::Conflict(format!("`{id}` is in the trash; restore it instead")));
+    }
+
+    let revision = revision::<T>(author, &id, action, None)?;
+    let created = repo.commit(Write::Create(data), revision, None).await?;
+    created.ok_or(APIError::InternalServerError)
+}
+
+/// Fields a replacement sent without them keeps from the current record,
+/// so a client that does not know about them cannot unpublish, unorder or re-date it.
+const KEPT_ON_REPLACE: [&str; 3] = ["status", "position", "published_at"];
+
+/// Replaces a record with a request body, see [`KEPT_ON_REPLACE`].
+pub async fn replace<T: Record>(
+    repo: &dyn Repository<T>,
+    id: &str,
+    mut body: Value,
+    author: &str,
+    expected: Option<&Stamp>,
+) -> Result<T, APIError> {
+    let current = document(&get(repo, id).await?)?;
+    if let Some(fields) = body.as_object_mut() {
+        for field in KEPT_ON_REPLACE {
+            if let Some(value) = current.get(field).filter(|value| !value.is_null()) {
+                fields.entry(field).or_insert_with(|| value.clone());
+            }
+        }
+    }
+    let data = serde_json::from_value(body).map_err(|e| APIError::Validation(e.to_string()))?;
+    update(repo, id, data, author, expected).await
+}
+
+/// Replaces a record. A replacement without a `created_at` keeps the current one.
+/// With an `expected` stamp, e.g. from a [`Precondition`](crate::routes::conditional::Precondition),
+/// the write fails with 412 Precondition Failed when the record was changed since.
+pub async fn update<T: Record>(
+    repo: &dyn Repository<T>,
+    id: &str,
+    data: T,
+    author: &str,
+    expected: Option<&Stamp>,
+) -> Result<T, APIError> {
+    update_as(repo, id, data, author, Action::Update, expected).await
+}
+
+/// [`update`], recorded as `action`.
+pub async fn update_as<T: Record>(
+    repo: &dyn Repository<T>,
+    id: &str,
+    mut data: T,
+    author: &str,
+    action: Action,
+    expected: Option<&Stamp>,
+) -> Result<T, APIError> {
+    data.prepare();
+    data.validate()?;
+    check_schedule(&data)?;
+    locale::check(&data)?;
+    if data.id().is_some_and(|record_id| record_id != id) {
+        return Err(APIError::Validation("record id cannot be changed".to_string()));
+    }
+    check_references(repo, &data).await?;
+    let current = repo.get(id).await?.ok_or(APIError::NotFound)?;
+    if data.created_at().is_none()
+        && let Some(created_at) = current.created_at()
+    {
+        data.set_created_at(created_at);
+    }
+
+    let revision = revision(author, id, action, Some(&current))?;
+    let updated = repo.commit(Write::Update(id.to_string(), data), revision, expected).await?;
+    updated.ok_or(APIError::NotFound)
+}
+
+/// Applies an RFC 7396 merge patch, validating the merged record before it is written.
+/// A plain string for a localized field only replaces the default locale's text.
+/// Fields [prepared](Record::prepare) from the merged record are added to the patch.
+/// `expected` is checked like in [`update`].
+pub async fn patch<T: Record>(
+    repo: &dyn Repository<T>,
+    id: &str,
+    patch: Value,
+    author: &str,
+    expected: Option<&Stamp>,
+) -> Result<T, APIError> {
+    if !patch.is_object() {
+        return Err(APIError::Validation("merge patch must be a JSON object".to_string()));
+    }
+    if patch.get("id").is_some() {
+        return Err(APIError::Validation("record id cannot be changed".to_string()));
+    }
+
+    let mut patch = locale::patch::<T>(patch);
+    let current = get(repo, id).await?;
+    let mut merged = patched(&current, &patch)?;
+    let unprepared = document(&merged)?;
+    merged.prepare();
+    merge_patch(&mut patch, &diff(&unprepared, &document(&merged)?));
+    merged.validate()?;
+    check_schedule(&merged)?;
+    locale::check(&merged)?;
+    if merged.id().is_some_and(|record_id| record_id != id) {
+        return Err(APIError::Validation("record id cannot be changed".to_string()));
+    }
+    check_references(repo, &merged).await?;
+
+    let revision = revision(author, id, Action::Update, Some(&current))?;
+    let updated = repo.commit(Write::Merge(id.to_string(), patch), revision, expected).await?;
+    updated.ok_or(APIError::NotFound)
+}
+
+/// Moves the record to `status`, e.g. publishes a draft, recorded as `action`.
+/// A record that goes live also gets what [`Record::on_publish`] sets.
+pub async fn set_status<T: Record>(
+    repo: &dyn Repository<T>,
+    id: &str,
+    status: Status,
+    author: &str,
+    action: Action,
+) -> Result<T, APIError> {
+    let current = get(repo, id).await?;
+    let mut patch = json!({ "status": status });
+    if status == Status::Published && current.status() != Status::Published {
+        merge_patch(&mut patch, &current.on_publish(Utc::now()));
+    }
+    let revision = revision(author, id, action, Some(&current))?;
+    let updated = repo.commit(Write::Merge(id.to_string(), patch), revision, None).await?;
+    updated.ok_or(APIError::NotFound)
+}
+
+/// Moves the record to the trash; see [`trash_service`](crate::services::trash_service).
+/// `expected` is checked like in [`update`].
+pub async fn delete<T: Record>(repo: &dyn Repository<T>, id: &str, author: &str, expected: Option<&Stamp>) -> Result<(), APIError> {
+    let current = get(repo, id).await?;
+    let revision = revision(author, id, Action::Delete, Some(&current))?;
+    repo.commit(Write::Merge(id.to_string(), json!({ "deleted_at": Utc::now() })), revision, expected).await?;
+    Ok(())
+}
+
+/// Rewrites the display order. `ids` must list every record exactly once.
+pub async fn reorder<T: Record>(repo: &dyn Repository<T>, ids: &[String]) -> Result<(), APIError> {
+    let existing: HashSet<String> = get_all(repo)
+        .await?
+        .iter()
+        .filter_map(|item| item.id().map(str::to_string))
+        .collect();
+    let requested: HashSet<String> = ids.iter().cloned().collect();
+
+    if requested.len() != ids.len() {
+        return Err(APIError::Validation("ids must not contain duplicates".to_string()));
+    }
+    if requested != existing {
+        return Err(APIError::Validation("ids must list every record exactly once".to_string()));
+    }
+
+    repo.reorder(ids).await
+}
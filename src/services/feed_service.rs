@@ -0,0 +1,187 @@
+use chrono::{DateTime, TimeZone, Utc};
+
+use crate::models::Record;
+use crate::models::about::Certificate;
+use crate::models::locale::default_locale;
+use crate::models::post::Post;
+use crate::models::revision::Revision;
+use crate::models::status::Viewer;
+use crate::services::{crud_service, revision_service};
+use crate::services::prelude::*;
+use crate::utils::escape_xml;
+
+/// Feeds are capped to the most recent entries.
+pub const MAX_ENTRIES: usize = 20;
+
+/// The date in the [tag URIs](https://www.rfc-editor.org/rfc/rfc4151) that
+/// identify entries. It only has to stay the same, so it is the year feeds
+/// were added.
+const TAG_DATE: &str = "2025";
+
+pub struct Site {
+    pub title: String,
+    pub url: String,
+}
+
+impl Site {
+    /// The host of `url`.
+    fn authority(&self) -> &str {
+        let host = self.url.split_once("://").map_or(self.url.as_str(), |(_, rest)| rest);
+        host.split(['/', ':']).next().unwrap_or(host)
+    }
+
+    /// A permanent id for the entry of record `id` of `T`, which unlike its
+    /// link stays the same when the record's URL changes.
+    fn entry_id<T: Record>(&self, id: &str) -> String {
+        format!("tag:{},{}:{}/{}", self.authority(), TAG_DATE, T::TABLE, id)
+    }
+}
+
+pub struct FeedEntry {
+    pub id: String,
+    pub title: String,
+    pub link: String,
+    pub summary: Option<String>,
+    pub categories: Vec<String>,
+    pub updated: DateTime<Utc>,
+}
+
+pub struct Feed {
+    pub entries: Vec<FeedEntry>,
+    /// When a collection in the feed was last written. Falls back to the
+    /// newest entry's date, or the Unix epoch for an empty feed, when no
+    /// write was recorded.
+    pub updated: DateTime<Utc>,
+}
+
+/// Collects the newest published posts, and certificates when a repository is given.
+/// Certificates are dated by when they were added, or January 1st of their
+/// year when they predate `created_at`.
+pub async fn build_feed(
+    site: &Site,
+    revisions: &dyn Repository<Revision>,
+    posts: &dyn Repository<Post>,
+    certificates: Option<&dyn Repository<Certificate>>,
+) -> Result<Feed, APIError> {
+    let mut last_write = revision_service::last_write::<Post>(revisions).await?;
+    let mut entries: Vec<FeedEntry> = crud_service::get_all(posts)
+        .await?
+        .into_iter()
+        .filter(|post| Viewer::Public.can_see(post))
+        .filter_map(|post| {
+            Some(FeedEntry {
+                id: site.entry_id::<Post>(&post.slug),
+                link: format!("{}/posts/{}", site.url, post.slug),
+                updated: post.published_at?,
+                title: post.title,
+                summary: None,
+                categories: post.tags,
+            })
+        })
+        .collect();
+
+    if let Some(certificates) = certificates {
+        last_write = last_write.max(revision_service::last_write::<Certificate>(revisions).await?);
+        for certificate in crud_service::get_all(certificates).await? {
+            if !Viewer::Public.can_see(&certificate) {
+                continue;
+            }
+            let Some(id) = certificate.id() else {
+                continue;
+            };
+            let Some(updated) = certificate
+                .created_at
+                .or_else(|| Utc.with_ymd_and_hms(certificate.year, 1, 1, 0, 0, 0).single())
+            else {
+                continue;
+            };
+            entries.push(FeedEntry {
+                id: site.entry_id::<Certificate>(id),
+                link: certificate
+                    .url
+                    .unwrap_or_else(|| format!("{}/certificates", site.url)),
+                title: certificate.title,
+                summary: certificate
+                    .description
+                    .map(|description| description.resolve(&default_locale()).to_string()),
+                categories: certificate.tags,
+                updated,
+            });
+        }
+    }
+
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.updated));
+    entries.truncate(MAX_ENTRIES);
+
+    let updated = last_write
+        .or_else(|| entries.first().map(|entry| entry.updated))
+        .unwrap_or(DateTime::UNIX_EPOCH);
+
+    Ok(Feed { entries, updated })
+}
+
+pub fn rss(site: &Site, feed: &Feed) -> String {
+    let mut xml = format!(
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+            r#"<rss version="2.0"><channel>"#,
+            "<title>{title}</title><link>{link}</link><description>{title}</description>",
+            "<lastBuildDate>{updated}</lastBuildDate>",
+        ),
+        title = escape_xml(&site.title),
+        link = escape_xml(&site.url),
+        updated = feed.updated.to_rfc2822(),
+    );
+
+    for entry in &feed.entries {
+        xml.push_str("<item>");
+        xml.push_str(&format!("<title>{}</title>", escape_xml(&entry.title)));
+        xml.push_str(&format!("<link>{}</link>", escape_xml(&entry.link)));
+        xml.push_str(&format!(r#"<guid isPermaLink="false">{}</guid>"#, escape_xml(&entry.id)));
+        if let Some(summary) = &entry.summary {
+            xml.push_str(&format!("<description>{}</description>", escape_xml(summary)));
+        }
+        for category in &entry.categories {
+            xml.push_str(&format!("<category>{}</category>", escape_xml(category)));
+        }
+        xml.push_str(&format!("<pubDate>{}</pubDate>", entry.updated.to_rfc2822()));
+        xml.push_str("</item>");
+    }
+
+    xml.push_str("</channel></rss>");
+    xml
+}
+
+pub fn atom(site: &Site, feed: &Feed) -> String {
+    let mut xml = format!(
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+            r#"<feed xmlns="http://www.w3.org/2005/Atom">"#,
+            "<title>{title}</title><id>{link}/</id>",
+            r#"<link href="{link}/"/><link rel="self" href="{link}/atom.xml"/>"#,
+            "<author><name>{title}</name></author>",
+            "<updated>{updated}</updated>",
+        ),
+        title = escape_xml(&site.title),
+        link = escape_xml(&site.url),
+        updated = feed.updated.to_rfc3339(),
+    );
+
+    for entry in &feed.entries {
+        xml.push_str("<entry>");
+        xml.push_str(&format!("<title>{}</title>", escape_xml(&entry.title)));
+        xml.push_str(&format!("<id>{}</id>", escape_xml(&entry.id)));
+        xml.push_str(&format!(r#"<link href="{}"/>"#, escape_xml(&entry.link)));
+        xml.push_str(&format!("<updated>{}</updated>", entry.updated.to_rfc3339()));
+        if let Some(summary) = &entry.summary {
+            xml.push_str(&format!("<summary>{}</summary>", escape_xml(summary)));
+        }
+        for category in &entry.categories {
+            xml.push_str(&format!(r#"<category term="{}"/>"#, escape_xml(category)));
+        }
+        xml.push_str("</entry>");
+    }
+
+    xml.push_str("</feed>");
+    xml
+}
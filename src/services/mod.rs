@@ -1,2 +1,10 @@
 pub mod about_service;
-pub mod prelude;
\ No newline at end of file
+pub mod crud_service;
+pub mod feed_service;
+pub mod prelude;
+pub mod revision_service;
+pub mod schedule_service;
+pub mod session_service;
+pub mod site_service;
+pub mod translation_service;
+pub mod trash_service;
@@ -1,3 +1,2 @@
-pub use crate::config::get_db;
 pub use crate::error::APIError;
-pub use surrealdb::RecordId;
\ No newline at end of file
+pub use crate::repository::Repository;
@@ -0,0 +1,85 @@
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::models::revision::{Action, Revision};
+use crate::models::{Record, document, from_document};
+use crate::services::crud_service;
+use crate::services::prelude::*;
+use crate::utils::diff;
+
+/// The revision of a write to the record `id` of `T::TABLE`, for
+/// [`Repository::commit`] to store with it. `previous` is the record as it
+/// was before the write, `None` when it did not exist yet.
+pub fn revision<T: Record>(author: &str, id: &str, action: Action, previous: Option<&T>) -> Result<Revision, APIError> {
+    Ok(Revision {
+        id: None,
+        collection: T::TABLE.to_string(),
+        record: id.to_string(),
+        action,
+        author: author.to_string(),
+        created_at: Utc::now(),
+        document: previous.map(document).transpose()?,
+    })
+}
+
+/// Revisions of one record, newest first.
+pub async fn history<T: Record>(revisions: &dyn Repository<Revision>, id: &str) -> Result<Vec<Revision>, APIError> {
+    let mut history = revisions.find(&[("collection", T::TABLE), ("record", id)]).await?;
+    Revision::sort(&mut history);
+    Ok(history)
+}
+
+/// When a record of `T::TABLE` was last written, if any write was recorded.
+pub async fn last_write<T: Record>(revisions: &dyn Repository<Revision>) -> Result<Option<DateTime<Utc>>, APIError> {
+    revisions.last_created(&[("collection", T::TABLE)]).await
+}
+
+/// A revision of the record `id`; 404 when `rev` belongs to another record.
+pub async fn get<T: Record>(revisions: &dyn Repository<Revision>, id: &str, rev: &str) -> Result<Revision, APIError> {
+    let revision = crud_service::get(revisions, rev).await?;
+    if revision.collection != T::TABLE || revision.record != id {
+        return Err(APIError::NotFound);
+    }
+    Ok(revision)
+}
+
+/// The merge patch that turns the document saved in revision `from` into the
+/// one saved in `to`, or into the current record when `to` is `None`.
+pub async fn diff_revisions<T: Record>(
+    revisions: &dyn Repository<Revision>,
+    repo: &dyn Repository<T>,
+    id: &str,
+    from: &str,
+    to: Option<&str>,
+) -> Result<Value, APIError> {
+    let from = get::<T>(revisions, id, from).await?.document.unwrap_or(Value::Null);
+    let to = match to {
+        Some(to) => get::<T>(revisions, id, to).await?.document.unwrap_or(Value::Null),
+        None => match repo.get(id).await? {
+            Some(current) => document(&current)?,
+            None => Value::Null,
+        },
+    };
+    Ok(diff(&from, &to))
+}
+
+/// Puts the record back to the document saved in revision `rev`, recreating
+/// it if it was deleted. The restore itself is recorded as a new revision.
+pub async fn restore<T: Record>(
+    revisions: &dyn Repository<Revision>,
+    repo: &dyn Repository<T>,
+    author: &str,
+    id: &str,
+    rev: &str,
+) -> Result<T, APIError> {
+    let revision = get::<T>(revisions, id, rev).await?;
+    let Some(saved) = revision.document else {
+        return Err(APIError::Validation("revision has no previous document to restore".to_string()));
+    };
+    let restored: T = from_document(saved, id)?;
+
+    match repo.get(id).await? {
+        Some(_) => crud_service::update_as(repo, id, restored, author, Action::Restore, None).await,
+        None => crud_service::create_as(repo, restored, author, Action::Restore).await,
+    }
+}
@@ -0,0 +1,135 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::json;
+
+use crate::clock::Clock;
+use crate::models::Record;
+use crate::models::about::About;
+use crate::models::site::Site;
+use crate::models::revision::Action;
+use crate::models::status::Status;
+use crate::services::prelude::*;
+use crate::repository::{ModelVisitor, Store, Write};
+use crate::services::{revision_service, site_service};
+use crate::utils::merge_patch;
+
+/// Author of the revisions the scheduler records.
+pub const SCHEDULER: &str = "scheduler";
+
+/// The schedulable records of one collection.
+#[async_trait]
+trait ScheduleBin: Send + Sync {
+    /// Applies every `publish_at`/`unpublish_at` that is due at `now`,
+    /// returning how many records changed.
+    async fn apply(&self, now: DateTime<Utc>) -> Result<usize, APIError>;
+}
+
+struct Bin<T>(Arc<dyn Repository<T>>);
+
+/// The schedulable collections of a site.
+#[derive(Default)]
+struct Bins(Vec<Box<dyn ScheduleBin>>);
+
+impl ModelVisitor for Bins {
+    fn visit<T: Record>(&mut self, repo: Arc<dyn Repository<T>>) {
+        if T::SCHEDULED {
+            self.0.push(Box::new(Bin(repo)));
+        }
+    }
+}
+
+#[async_trait]
+impl<T: Record> ScheduleBin for Bin<T> {
+    async fn apply(&self, now: DateTime<Utc>) -> Result<usize, APIError> {
+        let mut changed = 0;
+        for record in self.0.list().await? {
+            let Some(id) = record.id() else {
+                continue;
+            };
+            if record.deleted_at().is_some() {
+                continue;
+            }
+
+            let publish = record.publish_at().is_some_and(|at| at <= now);
+            let unpublish = record.unpublish_at().is_some_and(|at| at <= now);
+            if !publish && !unpublish {
+                continue;
+            }
+
+            // Both due means the record's whole window has passed
+            let (status, action) = if unpublish {
+                (Status::Draft, Action::Unpublish)
+            } else {
+                (Status::Published, Action::Publish)
+            };
+            let mut patch = json!({ "status": status });
+            if publish {
+                patch["publish_at"] = json!(null);
+            }
+            if status == Status::Published {
+                merge_patch(&mut patch, &record.on_publish(record.publish_at().unwrap_or(now)));
+            }
+            if unpublish {
+                patch["unpublish_at"] = json!(null);
+            }
+
+            let revision = revision_service::revision(SCHEDULER, id, action, Some(&record))?;
+            self.0.commit(Write::Merge(id.to_string(), patch), revision, None).await?;
+            changed += 1;
+        }
+        Ok(changed)
+    }
+}
+
+/// Publishes and unpublishes records whose `publish_at`/`unpublish_at` has
+/// come, on every site. The schedule is read from the records themselves, so
+/// it survives restarts.
+///
+/// ```ignore
+/// let scheduler = Scheduler::new(store, Arc::new(SystemClock));
+/// ```
+#[derive(Clone)]
+pub struct Scheduler {
+    store: Store,
+    clock: Arc<dyn Clock>,
+}
+
+impl Scheduler {
+    pub fn new(store: Store, clock: Arc<dyn Clock>) -> Self {
+        Self { store, clock }
+    }
+
+    /// The collections of `site` that can be scheduled.
+    fn bins(&self, site: &Site) -> Vec<Box<dyn ScheduleBin>> {
+        let mut bins = Bins::default();
+        self.store.visit(site, &mut bins);
+        bins.0
+    }
+
+    /// Applies everything that is due now, returning how many records changed.
+    pub async fn tick(&self) -> Result<usize, APIError> {
+        let now = self.clock.now();
+        let mut changed = 0;
+        for site in site_service::all(self.store.repository::<About>().as_ref()).await? {
+            for bin in self.bins(&site) {
+                changed += bin.apply(now).await?;
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Calls [`tick`](Self::tick) every `every` until the server shuts down.
+    pub async fn run(self, every: std::time::Duration) {
+        let mut interval = tokio::time::interval(every);
+        loop {
+            interval.tick().await;
+            match self.tick().await {
+                Ok(0) => {}
+                Ok(changed) => tracing::info!("⏰ Applied the publishing schedule of {} records", changed),
+                Err(e) => tracing::error!("❌ Failed to apply the publishing schedule: {:?}", e),
+            }
+        }
+    }
+}
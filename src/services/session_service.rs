@@ -0,0 +1,234 @@
+use std::env;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use uuid::Uuid;
+
+use crate::auth::oauth::UserProfile;
+use crate::auth::user::{self, Claims};
+use crate::models::Record;
+use crate::models::about::RecordIdReturn;
+use crate::models::session::{RevokedToken, Session};
+use crate::repository::Store;
+use crate::services::prelude::*;
+
+/// What a login or refresh answers with. The access token goes in the
+/// `Authorization` header until `expires_at`; the refresh token gets the
+/// next pair once, until `refresh_expires_at`.
+#[derive(Debug, Serialize)]
+pub struct SessionTokens {
+    pub token: String,
+    pub refresh_token: String,
+    pub expires_at: DateTime<Utc>,
+    pub refresh_expires_at: DateTime<Utc>,
+    #[serde(skip)]
+    pub session_id: String,
+}
+
+/// How long a session lasts without logging in again:
+/// `REFRESH_TOKEN_TTL_SECS`, 30 days by default.
+pub fn refresh_ttl() -> Duration {
+    env::var("REFRESH_TOKEN_TTL_SECS")
+        .ok()
+        .and_then(|secs| secs.parse().ok())
+        .map(Duration::seconds)
+        .unwrap_or_else(|| Duration::days(30))
+}
+
+/// The session id and secret of a refresh token.
+pub fn split(refresh_token: &str) -> Result<(&str, &str), APIError> {
+    refresh_token.split_once('.').ok_or(APIError::Unauthorized)
+}
+
+fn random_secret() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+fn hash(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))
+}
+
+/// Whether `secret` hashes to `expected`, compared in constant time.
+fn matches(secret: &str, expected: &str) -> bool {
+    hash(secret).as_bytes().ct_eq(expected.as_bytes()).into()
+}
+
+/// Gives `session` a new access token and refresh token, returning both.
+/// Refresh tokens are the session id and a secret only its hash is kept of.
+fn rotate(session: &mut Session) -> Result<(String, String), APIError> {
+    let id = session.id().ok_or(APIError::InternalServerError)?.to_string();
+    let secret = random_secret();
+    let previous = std::mem::replace(&mut session.refresh_hash, hash(&secret));
+    session.previous_hash = Some(previous).filter(|previous| !previous.is_empty());
+    session.access_jti = Uuid::new_v4().simple().to_string();
+    session.access_expires_at = Utc::now() + user::access_ttl();
+    Ok((user::issue(session)?, format!("{}.{}", id, secret)))
+}
+
+/// Stores `session` with fresh tokens and answers with them. The session is
+/// only replaced while it still has the refresh token it was read with, so
+/// of two refreshes with the same token only one gets a new pair.
+async fn save(sessions: &dyn Repository<Session>, mut session: Session) -> Result<SessionTokens, APIError> {
+    let current = session.refresh_hash.clone();
+    let (token, refresh_token) = rotate(&mut session)?;
+    let id = session.id().ok_or(APIError::InternalServerError)?.to_string();
+    let session = sessions
+        .update_if(&id, "refresh_hash", &current, session)
+        .await?
+        .ok_or(APIError::Unauthorized)?;
+    Ok(SessionTokens {
+        token,
+        refresh_token,
+        expires_at: session.access_expires_at,
+        refresh_expires_at: session.expires_at,
+        session_id: id,
+    })
+}
+
+/// Starts a session for a user who just logged in.
+pub async fn start(store: &Store, profile: UserProfile) -> Result<SessionTokens, APIError> {
+    let sessions = store.repository::<Session>();
+    let now = Utc::now();
+    let session = sessions
+        .create(Session {
+            id: None,
+            email: profile.email,
+            name: profile.name,
+            picture: profile.picture,
+            refresh_hash: String::new(),
+            previous_hash: None,
+            access_jti: String::new(),
+            access_expires_at: now,
+            created_at: now,
+            expires_at: now + refresh_ttl(),
+        })
+        .await?;
+    save(sessions.as_ref(), session).await
+}
+
+/// Trades a refresh token for a new pair, revoking the access token it was
+/// issued with. Presenting the refresh token the current one replaced means
+/// it leaked, so the whole session is revoked; the legitimate client has to
+/// log in again. Any other wrong secret is just rejected.
+pub async fn refresh(store: &Store, refresh_token: &str) -> Result<SessionTokens, APIError> {
+    let sessions = store.repository::<Session>();
+    let (id, secret) = split(refresh_token)?;
+    let session = sessions.get(id).await?.ok_or(APIError::Unauthorized)?;
+
+    if session.expires_at <= Utc::now() {
+        end(store, &session).await?;
+        return Err(APIError::Unauthorized);
+    }
+    if !matches(secret, &session.refresh_hash) {
+        if session.previous_hash.as_deref().is_some_and(|previous| matches(secret, previous)) {
+            end(store, &session).await?;
+        }
+        return Err(APIError::Unauthorized);
+    }
+
+    revoke(store, &session.access_jti, session.access_expires_at).await?;
+    save(sessions.as_ref(), session).await
+}
+
+/// Ends the session the access token of `claims` belongs to, revoking the token.
+pub async fn log_out(store: &Store, claims: &Claims) -> Result<(), APIError> {
+    revoke(store, &claims.jti, exp(claims)).await?;
+    if let Some(session) = store.repository::<Session>().get(&claims.sid).await? {
+        end(store, &session).await?;
+    }
+    Ok(())
+}
+
+/// Ends the session of `refresh_token`, for a client whose access token
+/// already expired. Only the session's current refresh token ends it.
+pub async fn log_out_with_refresh_token(store: &Store, refresh_token: &str) -> Result<(), APIError> {
+    let (id, secret) = split(refresh_token)?;
+    let session = store
+        .repository::<Session>()
+        .get(id)
+        .await?
+        .filter(|session| matches(secret, &session.refresh_hash))
+        .ok_or(APIError::Unauthorized)?;
+    end(store, &session).await
+}
+
+/// Ends every session of the user of `claims`, on every device, returning how many.
+pub async fn log_out_everywhere(store: &Store, claims: &Claims) -> Result<usize, APIError> {
+    revoke(store, &claims.jti, exp(claims)).await?;
+    let sessions = store.repository::<Session>().list().await?;
+    let mut ended = 0;
+    for session in sessions.iter().filter(|session| session.email.eq_ignore_ascii_case(&claims.email)) {
+        end(store, session).await?;
+        ended += 1;
+    }
+    Ok(ended)
+}
+
+fn exp(claims: &Claims) -> DateTime<Utc> {
+    DateTime::from_timestamp(claims.exp as i64, 0).unwrap_or_else(|| Utc::now() + user::access_ttl())
+}
+
+/// Deletes `session` and revokes its outstanding access token.
+async fn end(store: &Store, session: &Session) -> Result<(), APIError> {
+    revoke(store, &session.access_jti, session.access_expires_at).await?;
+    if let Some(id) = session.id() {
+        store.repository::<Session>().delete(id).await?;
+    }
+    Ok(())
+}
+
+async fn revoke(store: &Store, jti: &str, expires_at: DateTime<Utc>) -> Result<(), APIError> {
+    let revoked = store.repository::<RevokedToken>();
+    if revoked.get(jti).await?.is_none() {
+        revoked
+            .create(RevokedToken {
+                id: Some(RecordIdReturn(jti.to_string())),
+                expires_at,
+            })
+            .await?;
+    }
+    Ok(())
+}
+
+pub async fn is_revoked(store: &Store, jti: &str) -> Result<bool, APIError> {
+    Ok(store.repository::<RevokedToken>().get(jti).await?.is_some())
+}
+
+/// Deletes expired sessions, and revocations of tokens that expired anyway,
+/// returning how many.
+pub async fn prune(store: &Store) -> Result<usize, APIError> {
+    let now = Utc::now();
+    let mut pruned = 0;
+
+    let sessions = store.repository::<Session>();
+    for session in sessions.list().await?.iter().filter(|session| session.expires_at <= now) {
+        if let Some(id) = session.id() {
+            sessions.delete(id).await?;
+            pruned += 1;
+        }
+    }
+
+    let revoked = store.repository::<RevokedToken>();
+    for token in revoked.list().await?.iter().filter(|token| token.expires_at <= now) {
+        if let Some(id) = token.id() {
+            revoked.delete(id).await?;
+            pruned += 1;
+        }
+    }
+    Ok(pruned)
+}
+
+/// Prunes sessions and revocations every `every`, for as long as the server runs.
+pub async fn run_prune_job(store: Store, every: std::time::Duration) {
+    let mut interval = tokio::time::interval(every);
+    loop {
+        interval.tick().await;
+        match prune(&store).await {
+            Ok(0) => {}
+            Ok(pruned) => tracing::info!("🔑 Pruned {} expired sessions and revoked tokens", pruned),
+            Err(e) => tracing::error!("❌ Failed to prune sessions: {:?}", e),
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use std::env;
+
+use crate::models::about::{About, RecordIdReturn};
+use crate::models::site::{DefaultSite, Site};
+use crate::models::Record;
+use crate::services::crud_service;
+use crate::services::prelude::*;
+
+/// Lowercases a domain and drops any port and trailing dot.
+pub fn normalize(domain: &str) -> String {
+    let domain = domain.trim().to_ascii_lowercase();
+    let host = match domain.rsplit_once(':') {
+        Some((host, port)) if port.chars().all(|c| c.is_ascii_digit()) => host,
+        _ => domain.as_str(),
+    };
+    host.trim_end_matches('.').to_string()
+}
+
+/// The domain whose collections use the plain table names: `DEFAULT_SITE`
+/// or, when that is unset, the stored [`DefaultSite`]. Until one is stored
+/// it is the domain of the oldest About record, trashed or not, so a
+/// single-site deployment keeps its data where it was before sites existed;
+/// that choice is stored the first time it is made. About records written
+/// before they had a `created_at` count as oldest.
+async fn default_domain(about: &dyn Repository<About>) -> Result<Option<String>, APIError> {
+    if let Ok(domain) = env::var("DEFAULT_SITE") {
+        return Ok(Some(normalize(&domain)));
+    }
+
+    let stored = about.tables().repository::<DefaultSite>();
+    if let Some(default) = stored.get("default").await? {
+        return Ok(Some(normalize(&default.domain)));
+    }
+
+    let abouts = about.list().await?;
+    let Some(oldest) = abouts.into_iter().min_by_key(|about| about.created_at) else {
+        return Ok(None);
+    };
+    let default = DefaultSite {
+        id: Some(RecordIdReturn("default".to_string())),
+        domain: normalize(&oldest.domain),
+    };
+    let default = match stored.create(default).await {
+        Ok(default) => default,
+        // Another request stored it first
+        Err(APIError::Conflict(_)) => stored.get("default").await?.ok_or(APIError::InternalServerError)?,
+        Err(e) => return Err(e),
+    };
+    Ok(Some(default.domain))
+}
+
+/// Finds the site serving `domain`. Domains without an About record belong
+/// to the default site, unless the site was asked for `explicitly` (with
+/// `X-Site`), in which case an unknown domain is 404.
+pub async fn resolve(about: &dyn Repository<About>, domain: Option<&str>, explicitly: bool) -> Result<Site, APIError> {
+    let default = default_domain(about).await?;
+    let abouts = crud_service::get_all(about).await?;
+    let Some(domain) = domain.map(normalize) else {
+        return Ok(Site::default_for(default));
+    };
+    if default.as_ref() == Some(&domain) {
+        return Ok(Site::default_for(default));
+    }
+
+    let owner = abouts.into_iter().find(|about| normalize(&about.domain) == domain);
+
+    match owner.as_ref().and_then(|about| about.id()) {
+        Some(key) => Ok(Site::scoped(domain, key.to_string())),
+        None if explicitly => Err(APIError::NotFound),
+        None => Ok(Site::default_for(default.or(Some(domain)))),
+    }
+}
+
+/// The default site followed by every site with an About record.
+pub async fn all(about: &dyn Repository<About>) -> Result<Vec<Site>, APIError> {
+    sites(about, crud_service::get_all(about).await?).await
+}
+
+/// [`all`] sites and those whose About record is in the trash, which still
+/// have their tables.
+pub async fn with_tables(about: &dyn Repository<About>) -> Result<Vec<Site>, APIError> {
+    sites(about, about.list().await?).await
+}
+
+async fn sites(about: &dyn Repository<About>, abouts: Vec<About>) -> Result<Vec<Site>, APIError> {
+    let default = default_domain(about).await?;
+    let mut sites = vec![Site::default_for(default.clone())];
+
+    for record in abouts {
+        let domain = normalize(&record.domain);
+        if default.as_ref() == Some(&domain) {
+            continue;
+        }
+        if let Some(key) = record.id() {
+            sites.push(Site::scoped(domain, key.to_string()));
+        }
+    }
+    Ok(sites)
+}
@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::Serialize;
+
+use crate::models::{Record, locale};
+use crate::repository::ModelVisitor;
+use crate::services::crud_service;
+use crate::services::prelude::*;
+
+/// A localized field of a record that lacks some translations.
+#[derive(Debug, Serialize)]
+pub struct MissingTranslation {
+    pub collection: &'static str,
+    pub id: String,
+    pub field: &'static str,
+    /// Locales without text, in configured order
+    pub missing: Vec<String>,
+}
+
+/// The localized records of one collection.
+#[async_trait]
+trait TranslationBin: Send + Sync {
+    async fn missing(&self) -> Result<Vec<MissingTranslation>, APIError>;
+}
+
+struct Bin<T>(Arc<dyn Repository<T>>);
+
+#[async_trait]
+impl<T: Record> TranslationBin for Bin<T> {
+    async fn missing(&self) -> Result<Vec<MissingTranslation>, APIError> {
+        let mut items = Vec::new();
+        for record in crud_service::get_all(self.0.as_ref()).await? {
+            let Some(id) = record.id() else {
+                continue;
+            };
+            for (field, text) in locale::translations(&record)? {
+                let missing = text.missing();
+                if !missing.is_empty() {
+                    items.push(MissingTranslation {
+                        collection: T::TABLE,
+                        id: id.to_string(),
+                        field,
+                        missing,
+                    });
+                }
+            }
+        }
+        Ok(items)
+    }
+}
+
+/// The localized collections of a site.
+///
+/// ```ignore
+/// let translations = store.translations(&site);
+/// ```
+#[derive(Clone, Default)]
+pub struct Translations {
+    bins: Vec<Arc<dyn TranslationBin>>,
+}
+
+impl ModelVisitor for Translations {
+    fn visit<T: Record>(&mut self, repo: Arc<dyn Repository<T>>) {
+        if !T::LOCALIZED.is_empty() {
+            self.bins.push(Arc::new(Bin(repo)));
+        }
+    }
+}
+
+/// Every localized field that lacks a translation, or only those lacking
+/// one into `locale`.
+pub async fn missing(translations: &Translations, locale: Option<&str>) -> Result<Vec<MissingTranslation>, APIError> {
+    let mut items = Vec::new();
+    for bin in &translations.bins {
+        items.extend(bin.missing().await?);
+    }
+    if let Some(locale) = locale {
+        items.retain(|item| item.missing.iter().any(|missing| missing == locale));
+    }
+    Ok(items)
+}
@@ -0,0 +1,168 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::models::about::About;
+use crate::models::revision::Action;
+use crate::models::{Record, document};
+use crate::services::prelude::*;
+use crate::repository::{ModelVisitor, Store, Write};
+use crate::services::{revision_service, site_service};
+
+/// A record in the trash.
+#[derive(Debug, Serialize)]
+pub struct TrashedItem {
+    pub collection: &'static str,
+    pub id: String,
+    pub deleted_at: DateTime<Utc>,
+    pub document: Value,
+}
+
+/// The trashed records of one collection.
+#[async_trait]
+trait TrashBin: Send + Sync {
+    fn collection(&self) -> &'static str;
+    async fn list(&self) -> Result<Vec<TrashedItem>, APIError>;
+    async fn restore(&self, author: &str, id: &str) -> Result<Value, APIError>;
+    /// Permanently deletes records trashed before `before`, returning how many.
+    async fn purge(&self, before: DateTime<Utc>) -> Result<usize, APIError>;
+}
+
+struct Bin<T>(Arc<dyn Repository<T>>);
+
+#[async_trait]
+impl<T: Record> TrashBin for Bin<T> {
+    fn collection(&self) -> &'static str {
+        T::TABLE
+    }
+
+    async fn list(&self) -> Result<Vec<TrashedItem>, APIError> {
+        let mut items = Vec::new();
+        for record in self.0.list().await? {
+            if let (Some(id), Some(deleted_at)) = (record.id(), record.deleted_at()) {
+                items.push(TrashedItem {
+                    collection: T::TABLE,
+                    id: id.to_string(),
+                    deleted_at,
+                    document: document(&record)?,
+                });
+            }
+        }
+        Ok(items)
+    }
+
+    async fn restore(&self, author: &str, id: &str) -> Result<Value, APIError> {
+        let trashed = self
+            .0
+            .get(id)
+            .await?
+            .filter(|record| record.deleted_at().is_some())
+            .ok_or(APIError::NotFound)?;
+
+        let revision = revision_service::revision(author, id, Action::Restore, Some(&trashed))?;
+        let restored = self
+            .0
+            .commit(Write::Merge(id.to_string(), json!({ "deleted_at": null })), revision, None)
+            .await?
+            .ok_or(APIError::NotFound)?;
+        serde_json::to_value(restored).map_err(|_| APIError::InternalServerError)
+    }
+
+    async fn purge(&self, before: DateTime<Utc>) -> Result<usize, APIError> {
+        let mut purged = 0;
+        for record in self.0.list().await? {
+            if let (Some(id), Some(deleted_at)) = (record.id(), record.deleted_at())
+                && deleted_at < before
+            {
+                self.0.delete(id).await?;
+                purged += 1;
+            }
+        }
+        Ok(purged)
+    }
+}
+
+/// The trash of every soft-deletable collection of a site.
+///
+/// ```ignore
+/// let trash = store.trash(&site);
+/// ```
+#[derive(Clone, Default)]
+pub struct Trash {
+    bins: Vec<Arc<dyn TrashBin>>,
+}
+
+impl ModelVisitor for Trash {
+    fn visit<T: Record>(&mut self, repo: Arc<dyn Repository<T>>) {
+        self.bins.push(Arc::new(Bin(repo)));
+    }
+}
+
+impl Trash {
+    fn bin(&self, collection: &str) -> Result<&dyn TrashBin, APIError> {
+        self.bins
+            .iter()
+            .find(|bin| bin.collection() == collection)
+            .map(|bin| bin.as_ref())
+            .ok_or(APIError::NotFound)
+    }
+}
+
+/// Everything in the trash, most recently deleted first.
+pub async fn list(trash: &Trash) -> Result<Vec<TrashedItem>, APIError> {
+    let mut items = Vec::new();
+    for bin in &trash.bins {
+        items.extend(bin.list().await?);
+    }
+    items.sort_by_key(|item| std::cmp::Reverse(item.deleted_at));
+    Ok(items)
+}
+
+/// Takes a record out of the trash, recording the restore as a revision.
+pub async fn restore(trash: &Trash, author: &str, collection: &str, id: &str) -> Result<Value, APIError> {
+    trash.bin(collection)?.restore(author, id).await
+}
+
+/// Permanently deletes everything that has been in the trash longer than `retention`.
+pub async fn purge(trash: &Trash, retention: Duration) -> Result<usize, APIError> {
+    let before = Utc::now() - retention;
+    let mut purged = 0;
+    for bin in &trash.bins {
+        purged += bin.purge(before).await?;
+    }
+    Ok(purged)
+}
+
+/// [`purge`]s the trash of every site, including sites whose About record
+/// is in the trash. A site whose About record is purged goes with it: its
+/// tables are [dropped](Store::drop_tables).
+pub async fn purge_all(store: &Store, retention: Duration) -> Result<usize, APIError> {
+    let about = store.repository::<About>();
+    let sites = site_service::with_tables(about.as_ref()).await?;
+    let mut purged = 0;
+    for site in &sites {
+        purged += purge(&store.trash(site), retention).await?;
+    }
+
+    let remaining = site_service::with_tables(about.as_ref()).await?;
+    for site in sites.iter().filter(|site| !remaining.contains(site)) {
+        store.drop_tables(site).await?;
+    }
+    Ok(purged)
+}
+
+/// Runs [`purge_all`] every `every` until the server shuts down.
+pub async fn run_purge_job(store: Store, retention: Duration, every: std::time::Duration) {
+    let mut interval = tokio::time::interval(every);
+    loop {
+        interval.tick().await;
+        match purge_all(&store, retention).await {
+            Ok(0) => {}
+            Ok(purged) => tracing::info!("🗑️ Purged {} records from the trash", purged),
+            Err(e) => tracing::error!("❌ Failed to purge the trash: {:?}", e),
+        }
+    }
+}
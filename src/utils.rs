@@ -0,0 +1,106 @@
+/// A slug is a non-empty run of lowercase ASCII letters, digits and single hyphens.
+pub fn is_valid_slug(slug: &str) -> bool {
+    !slug.is_empty()
+        && !slug.starts_with('-')
+        && !slug.ends_with('-')
+        && !slug.contains("--")
+        && slug
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// Renders Markdown to HTML with anything unsafe (scripts, event handlers, ...) stripped.
+pub fn render_markdown(markdown: &str) -> String {
+    use pulldown_cmark::{Options, Parser, html};
+
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS;
+
+    let mut unsafe_html = String::new();
+    html::push_html(&mut unsafe_html, Parser::new_ext(markdown, options));
+
+    ammonia::clean(&unsafe_html)
+}
+
+/// Estimated reading time in minutes at 200 words per minute, never less than one.
+pub fn reading_time(text: &str) -> u32 {
+    let words = text.split_whitespace().count() as u32;
+    words.div_ceil(200).max(1)
+}
+
+/// A strong entity tag derived from the SHA-256 of `bytes`.
+pub fn etag(bytes: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+
+    hex::encode(&Sha256::digest(bytes)[..16])
+}
+
+/// Escapes the five XML special characters.
+pub fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Applies an RFC 7396 JSON Merge Patch to `target` in place.
+pub fn merge_patch(target: &mut serde_json::Value, patch: &serde_json::Value) {
+    use serde_json::{Map, Value};
+
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    let target = target.as_object_mut().expect("target is an object");
+
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(key);
+        } else {
+            merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
+        }
+    }
+}
+
+/// The JSON Merge Patch (RFC 7396) that turns `from` into `to`. As in merge
+/// patches, a `null` member is the same as a missing one.
+pub fn diff(from: &serde_json::Value, to: &serde_json::Value) -> serde_json::Value {
+    use serde_json::{Map, Value};
+
+    let (Value::Object(from), Value::Object(to)) = (from, to) else {
+        return to.clone();
+    };
+
+    let mut patch = Map::new();
+    for (key, old) in from {
+        if !old.is_null() && to.get(key).is_none_or(Value::is_null) {
+            patch.insert(key.clone(), Value::Null);
+        }
+    }
+    for (key, value) in to.iter().filter(|(_, value)| !value.is_null()) {
+        match from.get(key).filter(|old| !old.is_null()) {
+            Some(old) if old == value => {}
+            Some(old) => {
+                patch.insert(key.clone(), diff(old, value));
+            }
+            None => {
+                patch.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    Value::Object(patch)
+}
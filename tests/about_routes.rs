@@ -1,17 +1,243 @@
-use actix_web::{test, App};
+mod common;
 
-use portfolio_backend::routes;
+use actix_web::test;
+use serde_json::{json, Value};
+
+use portfolio_backend::repository::Store;
 
 #[actix_rt::test]
-async fn test_get_about_returns_not_implemented() {
-    let app = test::init_service(
-        App::new().configure(routes::config)
-    ).await;
+async fn test_get_about_returns_the_about_of_the_requested_host() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
 
     let req = test::TestRequest::get()
         .uri("/v1/about")
+        .insert_header(("Host", "kishor.dev"))
         .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
 
+    let req = test::TestRequest::post()
+        .uri("/v1/about")
+        .insert_header(common::bearer())
+        .set_json(common::about("kishor.dev", "Kishor"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+    let req = test::TestRequest::get()
+        .uri("/v1/about")
+        .insert_header(("Host", "Kishor.dev:443"))
+        .to_request();
+    let about: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(about["name"], "Kishor");
+}
+
+#[actix_rt::test]
+async fn test_certificate_crud_with_in_memory_store() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    let req = test::TestRequest::get().uri("/v1/certificates/all").to_request();
+    let page: Value = test::call_and_read_body_json(&app, req).await;
+    let list = page["items"].as_array().unwrap();
+    assert!(list.is_empty());
+
+    let req = test::TestRequest::post()
+        .uri("/v1/certificates")
+        .insert_header(common::bearer())
+        .set_json(common::certificate("Rust Fundamentals"))
+        .to_request();
     let resp = test::call_service(&app, req).await;
-    assert_eq!(resp.status(), 501); // Not Implemented
-}
\ No newline at end of file
+    assert_eq!(resp.status(), 201);
+    let created: Value = test::read_body_json(resp).await;
+    let id = created["id"].as_str().expect("created record has an id").to_string();
+
+    let mut updated = common::certificate("Rust Fundamentals");
+    updated["year"] = json!(2025);
+    let req = test::TestRequest::put()
+        .uri(&format!("/v1/certificates/{id}"))
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", "*"))
+        .set_json(updated)
+        .to_request();
+    let resp: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(resp["year"], 2025);
+    assert_eq!(resp["id"], id.as_str());
+
+    let req = test::TestRequest::get().uri(&format!("/v1/certificates/{id}")).to_request();
+    let fetched: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(fetched["year"], 2025);
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/v1/certificates/{id}"))
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", "*"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 204);
+
+    let req = test::TestRequest::get().uri(&format!("/v1/certificates/{id}")).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+}
+
+#[actix_rt::test]
+async fn test_create_requires_authentication() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    let req = test::TestRequest::post()
+        .uri("/v1/certificates")
+        .set_json(common::certificate("Rust Fundamentals"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+}
+
+#[actix_rt::test]
+async fn test_contact_is_a_singleton() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    let contact = common::contact();
+
+    let req = test::TestRequest::put()
+        .uri("/v1/contact")
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", "*"))
+        .set_json(&contact)
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    let req = test::TestRequest::post()
+        .uri("/v1/contact")
+        .insert_header(common::bearer())
+        .set_json(&contact)
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+    let req = test::TestRequest::post()
+        .uri("/v1/contact")
+        .insert_header(common::bearer())
+        .set_json(&contact)
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 409);
+
+    let req = test::TestRequest::get().uri("/v1/contact").to_request();
+    let contacts: Vec<Value> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(contacts.len(), 1);
+    assert_eq!(contacts[0]["personal_email"], "me@example.com");
+}
+
+#[actix_rt::test]
+async fn test_crud_resources_share_the_same_endpoints() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    let req = test::TestRequest::post()
+        .uri("/v1/programming-languages")
+        .insert_header(common::bearer())
+        .set_json(json!({ "name": "Rust", "level": "Advanced", "status": "published" }))
+        .to_request();
+    let created: Value = test::call_and_read_body_json(&app, req).await;
+    let id = created["id"].as_str().unwrap();
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/v1/programming-languages/{id}"))
+        .to_request();
+    let fetched: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(fetched["name"], "Rust");
+
+    let req = test::TestRequest::get().uri("/v1/about/all").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    let req = test::TestRequest::put()
+        .uri("/v1/spoken-languages/missing")
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", "*"))
+        .set_json(json!({ "name": "Tamil", "proficiency": "Native" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+}
+
+async fn list_endpoints_filter_sort_and_paginate(store: Store) {
+    let app = test::init_service(common::app(&store)).await;
+
+    for (title, year, tag, status) in [
+        ("B", 2023, "rust", "published"),
+        ("A", 2024, "rust", "published"),
+        ("C", 2024, "cloud", "published"),
+        ("D", 2024, "rust", "draft"),
+        ("E", 2024, "rust", "published"),
+    ] {
+        let mut payload = common::certificate(title);
+        payload["year"] = json!(year);
+        payload["tags"] = json!([tag]);
+        payload["status"] = json!(status);
+        let req = test::TestRequest::post()
+            .uri("/v1/certificates")
+            .insert_header(common::bearer())
+            .set_json(payload)
+            .to_request();
+        let created: Value = test::call_and_read_body_json(&app, req).await;
+
+        if title == "E" {
+            let req = test::TestRequest::delete()
+                .uri(&format!("/v1/certificates/{}", created["id"].as_str().unwrap()))
+                .insert_header(common::bearer())
+                .insert_header(("If-Match", "*"))
+                .to_request();
+            assert_eq!(test::call_service(&app, req).await.status(), 204);
+        }
+    }
+
+    // Admins also see drafts, but nobody lists the trash
+    let req = test::TestRequest::get()
+        .uri("/v1/certificates/all?tag=rust&sort=title")
+        .insert_header(common::bearer())
+        .to_request();
+    let page: Value = test::call_and_read_body_json(&app, req).await;
+    let titles: Vec<&str> = page["items"].as_array().unwrap().iter().map(|c| c["title"].as_str().unwrap()).collect();
+    assert_eq!(titles, ["A", "B", "D"]);
+
+    let req = test::TestRequest::get()
+        .uri("/v1/certificates/all?tag=rust&sort=title&order=desc")
+        .to_request();
+    let page: Value = test::call_and_read_body_json(&app, req).await;
+    let titles: Vec<&str> = page["items"].as_array().unwrap().iter().map(|c| c["title"].as_str().unwrap()).collect();
+    assert_eq!(titles, ["B", "A"]);
+    assert!(page["next_cursor"].is_null());
+
+    let req = test::TestRequest::get()
+        .uri("/v1/certificates/all?year=2024&sort=title&limit=1")
+        .to_request();
+    let page: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(page["items"][0]["title"], "A");
+    let cursor = page["next_cursor"].as_str().unwrap().to_string();
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/v1/certificates/all?year=2024&sort=title&limit=1&cursor={cursor}"))
+        .to_request();
+    let page: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(page["items"][0]["title"], "C");
+    assert!(page["next_cursor"].is_null());
+
+    for uri in [
+        "/v1/certificates/all?limit=0",
+        "/v1/certificates/all?order=sideways",
+        "/v1/certificates/all?sort=title;DROP",
+        "/v1/programming-languages?tag=rust",
+    ] {
+        let req = test::TestRequest::get().uri(uri).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400, "{uri}");
+    }
+}
+
+#[actix_rt::test]
+async fn test_list_endpoints_filter_sort_and_paginate_in_memory() {
+    list_endpoints_filter_sort_and_paginate(Store::in_memory()).await;
+}
+
+#[actix_rt::test]
+async fn test_list_endpoints_filter_sort_and_paginate_in_surreal() {
+    list_endpoints_filter_sort_and_paginate(common::surreal_store().await).await;
+}
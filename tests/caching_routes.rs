@@ -0,0 +1,102 @@
+mod common;
+
+use actix_web::{test, web, App};
+
+use portfolio_backend::models::about::TechStack;
+use portfolio_backend::repository::Store;
+use portfolio_backend::routes::{conditional::CachePolicy, crud::CrudResource};
+
+#[actix_rt::test]
+async fn test_public_reads_support_conditional_get() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    let req = test::TestRequest::get().uri("/v1/tech-stacks").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers().get("Cache-Control").unwrap(), CachePolicy::PROFILE.0);
+    let empty = resp.headers().get("ETag").unwrap().clone();
+
+    let req = test::TestRequest::get()
+        .uri("/v1/tech-stacks")
+        .insert_header(("If-None-Match", empty.clone()))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 304);
+    assert_eq!(resp.headers().get("ETag").unwrap(), &empty);
+    assert!(test::read_body(resp).await.is_empty());
+
+    let req = test::TestRequest::post()
+        .uri("/v1/tech-stacks")
+        .insert_header(common::bearer())
+        .set_json(common::tech_stack("Rust"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    let item_tag = resp.headers().get("ETag").unwrap().clone();
+    let created: serde_json::Value = test::read_body_json(resp).await;
+
+    let req = test::TestRequest::get()
+        .uri("/v1/tech-stacks")
+        .insert_header(("If-None-Match", empty))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/v1/tech-stacks/{}", created["id"].as_str().unwrap()))
+        .insert_header(("If-None-Match", item_tag))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 304);
+}
+
+#[actix_rt::test]
+async fn test_routes_are_registered_with_their_cache_policy() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    let req = test::TestRequest::post()
+        .uri("/v1/about")
+        .insert_header(common::bearer())
+        .set_json(common::about("kishor.dev", "Kishor"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+    for (uri, policy) in [
+        ("/v1/about", CachePolicy::PROFILE),
+        ("/v1/about/all", CachePolicy::PROFILE),
+        ("/v1/contact", CachePolicy::PROFILE),
+        ("/v1/projects", CachePolicy::PROFILE),
+        ("/v1/experience", CachePolicy::PROFILE),
+        ("/v1/posts", CachePolicy::DEFAULT),
+    ] {
+        let req = test::TestRequest::get().uri(uri).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200, "{uri}");
+        assert_eq!(resp.headers().get("Cache-Control").unwrap(), policy.0, "{uri}");
+    }
+
+    // Admins may see drafts, which are never stored
+    let req = test::TestRequest::get()
+        .uri("/v1/contact")
+        .insert_header(common::bearer())
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.headers().get("Cache-Control").unwrap(), "no-store");
+}
+
+#[actix_rt::test]
+async fn test_cache_control_is_configurable_per_resource() {
+    let store = Store::in_memory();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| store.configure(cfg))
+            .service(web::scope("/admin").configure(|cfg| {
+                CrudResource::<TechStack>::new("/tech-stacks")
+                    .cache_control(CachePolicy::NO_STORE)
+                    .register(cfg)
+            }))
+    ).await;
+
+    let req = test::TestRequest::get().uri("/admin/tech-stacks").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.headers().get("Cache-Control").unwrap(), "no-store");
+}
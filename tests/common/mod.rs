@@ -0,0 +1,140 @@
+#![allow(dead_code)]
+
+use actix_web::dev::{ServiceFactory, ServiceRequest, ServiceResponse};
+use actix_web::App;
+use chrono::{Duration, Utc};
+use jsonwebtoken::{EncodingKey, Header, encode};
+use serde_json::{Value, json};
+use surrealdb::{Surreal, engine::any::Any};
+
+use portfolio_backend::auth::user::Claims;
+use portfolio_backend::config;
+use portfolio_backend::repository::Store;
+use portfolio_backend::routes;
+
+pub const JWT_SECRET: &str = "test-secret";
+
+/// Mints a bearer token for the admin user, signed with [`JWT_SECRET`].
+pub fn admin_token() -> String {
+    // SAFETY: every test sets the same value, so concurrent writers never disagree.
+    unsafe { std::env::set_var("JWT_SECRET", JWT_SECRET) };
+
+    let claims = Claims {
+        email: "admin@example.com".to_string(),
+        name: "Admin".to_string(),
+        picture: String::new(),
+        jti: uuid::Uuid::new_v4().simple().to_string(),
+        sid: "test-session".to_string(),
+        exp: (Utc::now() + Duration::hours(1)).timestamp() as usize,
+    };
+
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+    )
+    .expect("failed to sign test token")
+}
+
+pub fn bearer() -> (&'static str, String) {
+    ("Authorization", format!("Bearer {}", admin_token()))
+}
+
+/// A fresh embedded SurrealDB, for tests that need the connection itself.
+pub async fn surreal_db() -> Surreal<Any> {
+    config::connect("mem://").await.expect("failed to start embedded SurrealDB")
+}
+
+/// A store backed by a fresh embedded SurrealDB.
+pub async fn surreal_store() -> Store {
+    Store::surreal(surreal_db().await)
+}
+
+/// The API backed by `store`, configured like the server does.
+pub fn app(
+    store: &Store,
+) -> App<
+    impl ServiceFactory<
+        ServiceRequest,
+        Config = (),
+        Response = ServiceResponse,
+        Error = actix_web::Error,
+        InitError = (),
+    > + use<>,
+> {
+    let store = store.clone();
+    App::new()
+        .configure(move |cfg| store.configure(cfg))
+        .configure(routes::config)
+}
+
+pub fn about(domain: &str, name: &str) -> Value {
+    json!({
+        "status": "published",
+        "domain": domain,
+        "name": name,
+        "headline": "Engineer",
+        "description": "Hello",
+        "location": { "city": "Chennai", "country": "India" },
+        "interests": ["rust"]
+    })
+}
+
+pub fn contact() -> Value {
+    json!({
+        "status": "published",
+        "professional_email": null,
+        "personal_email": "me@example.com",
+        "github": "gh",
+        "linkedin": "li",
+        "twitter": "tw",
+        "instagram": "ig"
+    })
+}
+
+pub fn certificate(title: &str) -> Value {
+    json!({
+        "status": "published",
+        "title": title,
+        "issuer": ["Rust Foundation"],
+        "url": null,
+        "year": 2024,
+        "description": null,
+        "image": null,
+        "tags": []
+    })
+}
+
+pub fn tech_stack(name: &str) -> Value {
+    json!({
+        "status": "published",
+        "name": name,
+        "description": null,
+        "logo": null,
+        "tags": []
+    })
+}
+
+pub fn project(slug: &str) -> Value {
+    json!({
+        "status": "published",
+        "slug": slug,
+        "title": "Portfolio Backend",
+        "summary": "The API behind my portfolio",
+        "body": "Built with actix-web.",
+        "repo_url": null,
+        "live_url": null,
+        "cover_image": null,
+        "year": { "from": 2025, "to": null }
+    })
+}
+
+pub fn post(slug: &str) -> Value {
+    json!({
+        "status": "published",
+        "slug": slug,
+        "title": format!("Post {slug}"),
+        "body": "Hello",
+        "tags": ["rust"]
+    })
+}
@@ -0,0 +1,183 @@
+mod common;
+
+use std::time::Duration;
+
+use actix_web::http::header::{EntityTag, IfMatch};
+use actix_web::test;
+use serde_json::{Value, json};
+
+use portfolio_backend::error::APIError;
+use portfolio_backend::models::about::Certificate;
+use portfolio_backend::repository::{ReadCache, Store};
+use portfolio_backend::routes::conditional::Precondition;
+use portfolio_backend::services::crud_service;
+
+#[actix_rt::test]
+async fn test_writes_require_matching_if_match() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    let req = test::TestRequest::post()
+        .uri("/v1/certificates")
+        .insert_header(common::bearer())
+        .set_json(common::certificate("Original"))
+        .to_request();
+    let created: Value = test::call_and_read_body_json(&app, req).await;
+    let uri = format!("/v1/certificates/{}", created["id"].as_str().unwrap());
+
+    let req = test::TestRequest::get().uri(&uri).to_request();
+    let resp = test::call_service(&app, req).await;
+    let first_tab = resp.headers().get("ETag").unwrap().to_str().unwrap().to_string();
+    let second_tab = first_tab.clone();
+
+    let req = test::TestRequest::put()
+        .uri(&uri)
+        .insert_header(common::bearer())
+        .set_json(common::certificate("No precondition"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 428);
+
+    let req = test::TestRequest::put()
+        .uri(&uri)
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", first_tab))
+        .set_json(common::certificate("First tab"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let latest = resp.headers().get("ETag").unwrap().to_str().unwrap().to_string();
+    assert_ne!(latest, second_tab);
+
+    let req = test::TestRequest::put()
+        .uri(&uri)
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", second_tab.as_str()))
+        .set_json(common::certificate("Second tab"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 412);
+
+    let req = test::TestRequest::delete()
+        .uri(&uri)
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", second_tab.as_str()))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 412);
+
+    let req = test::TestRequest::get().uri(&uri).to_request();
+    let fetched: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(fetched["title"], "First tab");
+
+    let req = test::TestRequest::delete()
+        .uri(&uri)
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", latest))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+}
+
+#[actix_rt::test]
+async fn test_contact_honors_if_match() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    let req = test::TestRequest::post()
+        .uri("/v1/contact")
+        .insert_header(common::bearer())
+        .set_json(common::contact())
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+    let req = test::TestRequest::patch()
+        .uri("/v1/contact")
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", "\"stale\""))
+        .insert_header(("Content-Type", "application/merge-patch+json"))
+        .set_payload(r#"{"github":"x"}"#)
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 412);
+
+    let req = test::TestRequest::delete()
+        .uri("/v1/contact")
+        .insert_header(common::bearer())
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 428);
+}
+
+/// Two tabs send the same `If-Match` and both pass the check before either writes.
+async fn only_one_of_two_racing_writes_lands(store: Store) {
+    let app = test::init_service(common::app(&store)).await;
+
+    let req = test::TestRequest::post()
+        .uri("/v1/certificates")
+        .insert_header(common::bearer())
+        .set_json(common::certificate("Original"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    let tag = resp.headers().get("ETag").unwrap().to_str().unwrap().trim_matches('"').to_string();
+    let created: Value = test::read_body_json(resp).await;
+    let id = created["id"].as_str().unwrap();
+
+    let repo = store.repository::<Certificate>();
+    let precondition = Precondition(IfMatch::Items(vec![EntityTag::new_strong(tag)]));
+    let first_tab = precondition.verify(repo.as_ref(), id).await.unwrap();
+    let second_tab = precondition.verify(repo.as_ref(), id).await.unwrap();
+
+    let patch = json!({ "title": "First tab" });
+    crud_service::patch(repo.as_ref(), id, patch, "admin@example.com", first_tab.as_ref()).await.unwrap();
+
+    let patch = json!({ "title": "Second tab" });
+    let lost = crud_service::patch(repo.as_ref(), id, patch, "admin@example.com", second_tab.as_ref()).await;
+    assert!(matches!(lost, Err(APIError::PreconditionFailed)));
+    let deleted = crud_service::delete(repo.as_ref(), id, "admin@example.com", second_tab.as_ref()).await;
+    assert!(matches!(deleted, Err(APIError::PreconditionFailed)));
+
+    assert_eq!(repo.get(id).await.unwrap().unwrap().title, "First tab");
+}
+
+#[actix_rt::test]
+async fn test_only_one_of_two_racing_writes_lands_in_memory() {
+    only_one_of_two_racing_writes_lands(Store::in_memory()).await;
+}
+
+#[actix_rt::test]
+async fn test_only_one_of_two_racing_writes_lands_in_surreal() {
+    only_one_of_two_racing_writes_lands(common::surreal_store().await).await;
+}
+
+#[actix_rt::test]
+async fn test_if_match_is_checked_against_the_store_not_the_cache() {
+    let backing = Store::in_memory();
+    let cached = backing.clone().cached(ReadCache::new(Duration::from_secs(60), 100));
+    let app = test::init_service(common::app(&cached)).await;
+    // Another server instance, with its own cache
+    let other = test::init_service(common::app(&backing.clone().cached(ReadCache::new(Duration::from_secs(60), 100)))).await;
+
+    let req = test::TestRequest::post()
+        .uri("/v1/certificates")
+        .insert_header(common::bearer())
+        .set_json(common::certificate("Original"))
+        .to_request();
+    let created: Value = test::call_and_read_body_json(&app, req).await;
+    let uri = format!("/v1/certificates/{}", created["id"].as_str().unwrap());
+
+    let req = test::TestRequest::get().uri(&uri).to_request();
+    let resp = test::call_service(&app, req).await;
+    let seen = resp.headers().get("ETag").unwrap().to_str().unwrap().to_string();
+
+    let req = test::TestRequest::patch()
+        .uri(&uri)
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", seen.as_str()))
+        .set_json(json!({ "title": "Elsewhere" }))
+        .to_request();
+    assert_eq!(test::call_service(&other, req).await.status(), 200);
+
+    // This instance still has the old version cached
+    let req = test::TestRequest::patch()
+        .uri(&uri)
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", seen.as_str()))
+        .set_json(json!({ "title": "Here" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 412);
+}
@@ -0,0 +1,74 @@
+mod common;
+
+use actix_web::test;
+use serde_json::{json, Value};
+
+use portfolio_backend::repository::Store;
+
+fn experience(role: &str, from: (i32, u8), to: Option<(i32, u8)>) -> Value {
+    json!({
+        "status": "published",
+        "company": "Acme",
+        "role": role,
+        "type": "FullTime",
+        "location": { "city": "Chennai", "country": "India" },
+        "remote": false,
+        "period": {
+            "from": { "year": from.0, "month": from.1 },
+            "to": to.map(|(year, month)| json!({ "year": year, "month": month })),
+        },
+        "highlights": ["Shipped things"],
+        "tech_stacks": []
+    })
+}
+
+async fn experience_is_listed_newest_first(store: Store) {
+    let app = test::init_service(common::app(&store)).await;
+
+    for payload in [
+        experience("Intern", (2022, 5), Some((2022, 8))),
+        experience("Engineer", (2024, 1), None),
+        experience("Junior", (2022, 9), Some((2023, 12))),
+    ] {
+        let req = test::TestRequest::post()
+            .uri("/v1/experience")
+            .insert_header(common::bearer())
+            .set_json(payload)
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 201);
+    }
+
+    let req = test::TestRequest::get().uri("/v1/experience").to_request();
+    let page: Value = test::call_and_read_body_json(&app, req).await;
+    let list = page["items"].as_array().unwrap();
+    let roles: Vec<&str> = list.iter().map(|item| item["role"].as_str().unwrap()).collect();
+    assert_eq!(roles, ["Engineer", "Junior", "Intern"]);
+}
+
+#[actix_rt::test]
+async fn test_experience_is_listed_newest_first_in_memory() {
+    experience_is_listed_newest_first(Store::in_memory()).await;
+}
+
+#[actix_rt::test]
+async fn test_experience_is_listed_newest_first_in_surreal() {
+    experience_is_listed_newest_first(common::surreal_store().await).await;
+}
+
+#[actix_rt::test]
+async fn test_experience_period_is_validated() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    for payload in [
+        experience("Engineer", (2024, 13), None),
+        experience("Engineer", (2024, 6), Some((2023, 1))),
+    ] {
+        let req = test::TestRequest::post()
+            .uri("/v1/experience")
+            .insert_header(common::bearer())
+            .set_json(payload)
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+    }
+}
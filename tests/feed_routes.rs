@@ -0,0 +1,117 @@
+mod common;
+
+use actix_web::test;
+use serde_json::json;
+
+use portfolio_backend::repository::Store;
+
+#[actix_rt::test]
+async fn test_feeds_escape_content_and_support_conditional_get() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    let mut post = common::post("tips");
+    post["title"] = json!("Rust <tips> & tricks");
+    post["published_at"] = json!("2025-03-01T12:00:00Z");
+    let req = test::TestRequest::post()
+        .uri("/v1/posts")
+        .insert_header(common::bearer())
+        .set_json(post)
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+    let req = test::TestRequest::get().uri("/feed.xml").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let etag = resp.headers().get("ETag").unwrap().clone();
+    let last_modified = resp.headers().get("Last-Modified").unwrap().clone();
+    let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    assert!(body.contains("<title>Rust &lt;tips&gt; &amp; tricks</title>"));
+    assert!(body.contains(r#"<guid isPermaLink="false">tag:localhost,2025:posts/tips</guid>"#));
+    assert!(body.contains("<pubDate>Sat, 1 Mar 2025 12:00:00 +0000</pubDate>"));
+    // Dated by the last write, not by the newest entry
+    assert!(!body.contains("<lastBuildDate>Sat, 1 Mar 2025"));
+
+    let req = test::TestRequest::get()
+        .uri("/feed.xml")
+        .insert_header(("If-None-Match", etag))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 304);
+
+    let req = test::TestRequest::get()
+        .uri("/atom.xml")
+        .insert_header(("If-Modified-Since", last_modified))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 304);
+
+    let req = test::TestRequest::get()
+        .uri("/atom.xml")
+        .insert_header(("If-Modified-Since", "Sat, 01 Mar 2025 12:00:00 GMT"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    let req = test::TestRequest::get().uri("/atom.xml").to_request();
+    let body = String::from_utf8(test::call_and_read_body(&app, req).await.to_vec()).unwrap();
+    assert!(body.contains("<id>tag:localhost,2025:posts/tips</id>"));
+    assert!(body.contains("<updated>2025-03-01T12:00:00+00:00</updated>"));
+    assert!(body.contains(r#"<category term="rust"/>"#));
+}
+
+#[actix_rt::test]
+async fn test_feed_can_include_certificates() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    let mut certificate = common::certificate("Certified Rustacean");
+    certificate["url"] = json!("https://example.com/cert");
+    let req = test::TestRequest::post()
+        .uri("/v1/certificates")
+        .insert_header(common::bearer())
+        .set_json(certificate)
+        .to_request();
+    let created: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let id = created["id"].as_str().unwrap();
+    let created_at = chrono::DateTime::parse_from_rfc3339(created["created_at"].as_str().unwrap()).unwrap();
+
+    let req = test::TestRequest::get().uri("/feed.xml").to_request();
+    let body = String::from_utf8(test::call_and_read_body(&app, req).await.to_vec()).unwrap();
+    assert!(!body.contains("Certified Rustacean"));
+
+    let req = test::TestRequest::get().uri("/feed.xml?certificates=true").to_request();
+    let body = String::from_utf8(test::call_and_read_body(&app, req).await.to_vec()).unwrap();
+    assert!(body.contains("<link>https://example.com/cert</link>"));
+    assert!(body.contains(&format!("tag:localhost,2025:certificates/{id}</guid>")));
+    assert!(body.contains(&format!("<pubDate>{}</pubDate>", created_at.to_rfc2822())));
+}
+
+#[actix_rt::test]
+async fn test_feed_links_point_at_the_requested_site() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    for (domain, name) in [("kishor.dev", "Kishor"), ("blog.example.com", "Blog")] {
+        let req = test::TestRequest::post()
+            .uri("/v1/about")
+            .insert_header(common::bearer())
+            .set_json(common::about(domain, name))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 201);
+    }
+
+    let req = test::TestRequest::post()
+        .uri("/v1/posts")
+        .insert_header(common::bearer())
+        .insert_header(("X-Site", "blog.example.com"))
+        .set_json(common::post("hello"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+    let req = test::TestRequest::get()
+        .uri("/feed.xml")
+        .insert_header(("Host", "blog.example.com"))
+        .to_request();
+    let body = String::from_utf8(test::call_and_read_body(&app, req).await.to_vec()).unwrap();
+    assert!(body.contains("<title>Blog</title>"));
+    assert!(body.contains("<link>http://blog.example.com/posts/hello</link>"));
+    assert!(body.contains("tag:blog.example.com,2025:posts/hello</guid>"));
+}
@@ -0,0 +1,164 @@
+mod common;
+
+use actix_web::test;
+use serde_json::{json, Value};
+
+use portfolio_backend::repository::Store;
+
+fn about() -> Value {
+    let mut about = common::about("kishor.dev", "Kishor");
+    about["headline"] = json!({ "en": "Engineer", "ta": "பொறியாளர்" });
+    about
+}
+
+async fn content_is_negotiated(store: Store) {
+    let app = test::init_service(common::app(&store)).await;
+
+    let req = test::TestRequest::post()
+        .uri("/v1/about")
+        .insert_header(common::bearer())
+        .set_json(about())
+        .to_request();
+    let created: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(created["headline"], "Engineer");
+    let uri = format!("/v1/about/{}", created["id"].as_str().unwrap());
+
+    let req = test::TestRequest::get()
+        .uri(&uri)
+        .insert_header(("Accept-Language", "ta-IN, en;q=0.5"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.headers().get("Content-Language").unwrap(), "ta");
+    let vary: Vec<_> = resp.headers().get_all("Vary").collect();
+    assert_eq!(vary, ["Host", "X-Site", "Accept-Language"]);
+    let tamil_tag = resp.headers().get("ETag").unwrap().clone();
+    let fetched: Value = test::read_body_json(resp).await;
+    assert_eq!(fetched["headline"], "பொறியாளர்");
+    // Falls back to the default locale when there is no translation
+    assert_eq!(fetched["description"], "Hello");
+
+    let req = test::TestRequest::get()
+        .uri(&format!("{uri}?lang=en"))
+        .insert_header(("Accept-Language", "ta"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    // Each locale is a different representation, so it has its own tag
+    assert_ne!(resp.headers().get("ETag").unwrap(), &tamil_tag);
+    let fetched: Value = test::read_body_json(resp).await;
+    assert_eq!(fetched["headline"], "Engineer");
+
+    let req = test::TestRequest::get()
+        .uri(&uri)
+        .insert_header(("Accept-Language", "ta"))
+        .insert_header(("If-None-Match", tamil_tag.clone()))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 304);
+
+    let req = test::TestRequest::get()
+        .uri(&uri)
+        .insert_header(("Accept-Language", "fr-FR"))
+        .to_request();
+    let fetched: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(fetched["headline"], "Engineer");
+
+    let req = test::TestRequest::get().uri(&format!("{uri}?lang=all")).to_request();
+    let fetched: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(fetched["headline"], json!({ "en": "Engineer", "ta": "பொறியாளர்" }));
+    assert_eq!(fetched["description"], json!({ "en": "Hello" }));
+
+    let req = test::TestRequest::get().uri(&format!("{uri}?lang=fr")).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+    let req = test::TestRequest::get().uri("/v1/about/all?lang=ta").to_request();
+    let page: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(page["items"][0]["headline"], "பொறியாளர்");
+
+    // A plain string only replaces the default locale's text. The tag of any
+    // locale satisfies If-Match.
+    let req = test::TestRequest::patch()
+        .uri(&uri)
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", tamil_tag))
+        .insert_header(("Content-Type", "application/merge-patch+json"))
+        .set_payload(json!({ "headline": "Rustacean", "description": { "ta": "வணக்கம்" } }).to_string())
+        .to_request();
+    let patched: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(patched["headline"], "Rustacean");
+
+    let req = test::TestRequest::get().uri(&format!("{uri}?lang=all")).to_request();
+    let fetched: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(fetched["headline"], json!({ "en": "Rustacean", "ta": "பொறியாளர்" }));
+    assert_eq!(fetched["description"], json!({ "en": "Hello", "ta": "வணக்கம்" }));
+}
+
+#[actix_rt::test]
+async fn test_content_is_negotiated_in_memory() {
+    content_is_negotiated(Store::in_memory()).await;
+}
+
+#[actix_rt::test]
+async fn test_content_is_negotiated_in_surreal() {
+    content_is_negotiated(common::surreal_store().await).await;
+}
+
+#[actix_rt::test]
+async fn test_unsupported_locales_are_rejected() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    let mut body = about();
+    body["headline"] = json!({ "en": "Engineer", "fr": "Ingénieur" });
+    let req = test::TestRequest::post()
+        .uri("/v1/about")
+        .insert_header(common::bearer())
+        .set_json(body)
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+}
+
+#[actix_rt::test]
+async fn test_admin_lists_missing_translations() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    let req = test::TestRequest::get().uri("/v1/translations/missing").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 401);
+
+    let req = test::TestRequest::post()
+        .uri("/v1/about")
+        .insert_header(common::bearer())
+        .set_json(about())
+        .to_request();
+    let created: Value = test::call_and_read_body_json(&app, req).await;
+
+    let mut certificate = common::certificate("Rust Fundamentals");
+    certificate["description"] = json!({ "en": "Ownership and borrowing", "ta": "உரிமை" });
+    let req = test::TestRequest::post()
+        .uri("/v1/certificates")
+        .insert_header(common::bearer())
+        .set_json(certificate)
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+    let req = test::TestRequest::get()
+        .uri("/v1/translations/missing?lang=ta")
+        .insert_header(common::bearer())
+        .to_request();
+    let missing: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(
+        missing,
+        json!([{
+            "collection": "about",
+            "id": created["id"],
+            "field": "description",
+            "missing": ["ta"]
+        }])
+    );
+
+    let req = test::TestRequest::get()
+        .uri("/v1/translations/missing?lang=en")
+        .insert_header(common::bearer())
+        .to_request();
+    let missing: Value = test::call_and_read_body_json(&app, req).await;
+    assert!(missing.as_array().unwrap().is_empty());
+}
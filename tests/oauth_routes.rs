@@ -0,0 +1,432 @@
+mod common;
+
+use std::net::SocketAddr;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use actix_web::cookie::{Cookie, SameSite};
+use actix_web::dev::ServiceResponse;
+use actix_web::{test, web, App, HttpRequest, HttpResponse, HttpServer};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use portfolio_backend::auth::github::GitHub;
+use portfolio_backend::auth::oauth::{Endpoints, LoginState, ProviderConfig, Providers, STATE_COOKIE};
+use portfolio_backend::auth::oidc::{Metadata, Oidc};
+use portfolio_backend::auth::session::{SESSION_COOKIE, SessionMode};
+use portfolio_backend::auth::user::Claims;
+use portfolio_backend::repository::Store;
+
+fn configure_google() {
+    common::admin_token();
+    // SAFETY: every test sets the same values, so concurrent writers never disagree.
+    unsafe {
+        std::env::set_var("GOOGLE_CLIENT_ID", "client-id");
+        std::env::set_var("GOOGLE_CLIENT_SECRET", "client-secret");
+        std::env::set_var("GOOGLE_REDIRECT_URI", "http://localhost:8080/v1/auth/google/callback");
+    }
+}
+
+fn query_param(url: &str, name: &str) -> String {
+    let query = url.split_once('?').unwrap().1;
+    query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix(&format!("{name}=")))
+        .unwrap_or_else(|| panic!("{name} is missing from {url}"))
+        .to_string()
+}
+
+fn state_cookie<B>(resp: &ServiceResponse<B>) -> Cookie<'static> {
+    resp.response()
+        .cookies()
+        .find(|cookie| cookie.name() == STATE_COOKIE)
+        .expect("login sets the state cookie")
+        .into_owned()
+}
+
+#[actix_rt::test]
+async fn test_login_sends_state_and_pkce_challenge() {
+    configure_google();
+    let app = test::init_service(common::app(&Store::in_memory())).await;
+
+    let req = test::TestRequest::get().uri("/v1/auth/google/login").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 302);
+
+    let location = resp.headers().get("Location").unwrap().to_str().unwrap().to_string();
+    assert_eq!(query_param(&location, "code_challenge_method"), "S256");
+
+    let cookie = state_cookie(&resp);
+    assert_eq!(cookie.http_only(), Some(true));
+    assert_eq!(cookie.secure(), Some(true));
+    assert_eq!(cookie.same_site(), Some(SameSite::Lax));
+
+    let mut validation = Validation::default();
+    validation.set_audience(&["oauth-state"]);
+    let login = decode::<LoginState>(cookie.value(), &DecodingKey::from_secret(common::JWT_SECRET.as_bytes()), &validation)
+        .unwrap()
+        .claims;
+    assert_eq!(query_param(&location, "state"), login.state);
+    assert_eq!(
+        query_param(&location, "code_challenge"),
+        URL_SAFE_NO_PAD.encode(Sha256::digest(login.verifier.as_bytes()))
+    );
+
+    // Every login gets its own state
+    let req = test::TestRequest::get().uri("/v1/auth/google/login").to_request();
+    let other = test::call_service(&app, req).await;
+    let other = other.headers().get("Location").unwrap().to_str().unwrap();
+    assert_ne!(query_param(other, "state"), login.state);
+}
+
+#[actix_rt::test]
+async fn test_callback_rejects_missing_or_mismatched_state() {
+    configure_google();
+    let app = test::init_service(common::app(&Store::in_memory())).await;
+
+    let req = test::TestRequest::get().uri("/v1/auth/google/login").to_request();
+    let resp = test::call_service(&app, req).await;
+    let location = resp.headers().get("Location").unwrap().to_str().unwrap().to_string();
+    let state = query_param(&location, "state");
+    let cookie = state_cookie(&resp);
+
+    // No cookie: the login was not started from this browser
+    let req = test::TestRequest::get()
+        .uri(&format!("/v1/auth/google/callback?code=abc&state={state}"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+    let req = test::TestRequest::get()
+        .uri("/v1/auth/google/callback?code=abc")
+        .cookie(cookie.clone())
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+    let req = test::TestRequest::get()
+        .uri("/v1/auth/google/callback?code=abc&state=forged")
+        .cookie(cookie)
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 401);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/v1/auth/google/callback?code=abc&state={state}"))
+        .cookie(Cookie::new(STATE_COOKIE, "not-a-signed-state"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 401);
+}
+
+const ACCESS_TOKEN: &str = "mock-access-token";
+
+/// Signing keys of the mock issuer, as a private key and its JWK.
+const KEYS: [(&str, &str, &str); 2] = [
+    ("key-1", include_str!("fixtures/oidc_key1.pem"), include_str!("fixtures/oidc_key1.jwk.json")),
+    ("key-2", include_str!("fixtures/oidc_key2.pem"), include_str!("fixtures/oidc_key2.jwk.json")),
+];
+
+/// A local stand-in for the providers: an OpenID Connect issuer that also
+/// serves GitHub's profile endpoints.
+#[derive(Default)]
+struct MockProvider {
+    /// Index into [`KEYS`] of the key that signs and is published
+    key: AtomicUsize,
+    jwks_fetches: AtomicUsize,
+    /// The nonce of the login in progress
+    nonce: Mutex<String>,
+    /// Claims that replace the defaults of the next ID tokens
+    overrides: Mutex<Value>,
+}
+
+fn issuer(req: &HttpRequest) -> String {
+    format!("http://{}", req.connection_info().host())
+}
+
+fn authorized(req: &HttpRequest) -> bool {
+    req.headers()
+        .get("Authorization")
+        .is_some_and(|value| value == format!("Bearer {ACCESS_TOKEN}").as_str())
+}
+
+async fn mock_discovery(req: HttpRequest) -> HttpResponse {
+    let issuer = issuer(&req);
+    HttpResponse::Ok().json(json!({
+        "issuer": issuer,
+        "authorization_endpoint": format!("{issuer}/authorize"),
+        "token_endpoint": format!("{issuer}/token"),
+        "jwks_uri": format!("{issuer}/jwks"),
+        "userinfo_endpoint": format!("{issuer}/userinfo")
+    }))
+}
+
+async fn mock_jwks(mock: web::Data<MockProvider>) -> HttpResponse {
+    mock.jwks_fetches.fetch_add(1, Ordering::SeqCst);
+    let (_, _, jwk) = KEYS[mock.key.load(Ordering::SeqCst)];
+    let jwk: Value = serde_json::from_str(jwk).unwrap();
+    HttpResponse::Ok().json(json!({ "keys": [jwk] }))
+}
+
+async fn mock_token(
+    req: HttpRequest,
+    mock: web::Data<MockProvider>,
+    form: web::Form<std::collections::HashMap<String, String>>,
+) -> HttpResponse {
+    let valid = form.get("code").is_some_and(|code| code == "good-code")
+        && form.get("client_secret").is_some_and(|secret| secret == "client-secret")
+        && form.get("code_verifier").is_some_and(|verifier| verifier.len() >= 43);
+    if !valid {
+        // GitHub reports a bad code with a 200
+        return HttpResponse::Ok().json(json!({ "error": "bad_verification_code" }));
+    }
+
+    let mut claims = json!({
+        "iss": issuer(&req),
+        "aud": "client-id",
+        "sub": "1234",
+        "exp": chrono::Utc::now().timestamp() + 300,
+        "email": "admin@example.com",
+        "email_verified": true,
+        "name": "Admin",
+        "nonce": *mock.nonce.lock().unwrap(),
+    });
+    if let Value::Object(overrides) = &*mock.overrides.lock().unwrap() {
+        for (claim, value) in overrides {
+            claims[claim] = value.clone();
+        }
+    }
+
+    let (kid, pem, _) = KEYS[mock.key.load(Ordering::SeqCst)];
+    let mut header = Header::new(Algorithm::RS256);
+    header.kid = Some(kid.to_string());
+    let id_token = encode(&header, &claims, &EncodingKey::from_rsa_pem(pem.as_bytes()).unwrap()).unwrap();
+    HttpResponse::Ok().json(json!({ "access_token": ACCESS_TOKEN, "token_type": "bearer", "id_token": id_token }))
+}
+
+async fn mock_github_user(req: HttpRequest) -> HttpResponse {
+    if !authorized(&req) || req.headers().get("User-Agent").is_none() {
+        return HttpResponse::Unauthorized().finish();
+    }
+    HttpResponse::Ok().json(json!({ "login": "kishor", "name": null, "avatar_url": "https://example.com/avatar.png" }))
+}
+
+async fn mock_github_emails(req: HttpRequest) -> HttpResponse {
+    if !authorized(&req) {
+        return HttpResponse::Unauthorized().finish();
+    }
+    HttpResponse::Ok().json(json!([
+        { "email": "public@example.com", "primary": false, "verified": true },
+        { "email": "admin@example.com", "primary": true, "verified": true }
+    ]))
+}
+
+async fn mock_provider() -> (SocketAddr, web::Data<MockProvider>) {
+    let mock = web::Data::new(MockProvider::default());
+    let data = mock.clone();
+    let server = HttpServer::new(move || {
+        App::new()
+            .app_data(data.clone())
+            .route("/.well-known/openid-configuration", web::get().to(mock_discovery))
+            .route("/jwks", web::get().to(mock_jwks))
+            .route("/token", web::post().to(mock_token))
+            .route("/github/user", web::get().to(mock_github_user))
+            .route("/github/user/emails", web::get().to(mock_github_emails))
+    })
+    .workers(1)
+    .bind(("127.0.0.1", 0))
+    .unwrap();
+    let addr = server.addrs()[0];
+    actix_rt::spawn(server.run());
+    (addr, mock)
+}
+
+fn mock_config(addr: SocketAddr, provider: &str, userinfo: &str) -> ProviderConfig {
+    ProviderConfig {
+        client_id: "client-id".to_string(),
+        client_secret: "client-secret".to_string(),
+        redirect_uri: format!("http://localhost:8080/v1/auth/{provider}/callback"),
+        endpoints: Endpoints {
+            auth_url: format!("http://{addr}/authorize"),
+            token_url: format!("http://{addr}/token"),
+            userinfo_url: format!("http://{addr}{userinfo}"),
+        },
+    }
+}
+
+fn mock_oidc(addr: SocketAddr, name: &str) -> Oidc {
+    let metadata = Metadata {
+        issuers: vec![format!("http://{addr}")],
+        jwks_uri: format!("http://{addr}/jwks"),
+    };
+    Oidc::new(name, mock_config(addr, name, "/userinfo"), metadata)
+}
+
+fn mock_providers(addr: SocketAddr) -> Providers {
+    Providers::default()
+        .with(mock_oidc(addr, "google"))
+        .with(GitHub::new(mock_config(addr, "github", "/github/user")))
+}
+
+fn configure_mock() {
+    common::admin_token();
+    // SAFETY: every test sets the same value, so concurrent writers never disagree.
+    unsafe { std::env::set_var("ALLOWED_EMAIL", "admin@example.com") };
+}
+
+/// Starts a login and follows the provider back to the callback with `code`,
+/// handing the login's nonce to the mock issuer on the way.
+macro_rules! log_in {
+    ($app:expr, $mock:expr, $provider:expr, $code:expr) => {{
+        let (provider, code) = ($provider, $code);
+        let req = test::TestRequest::get().uri(&format!("/v1/auth/{provider}/login")).to_request();
+        let resp = test::call_service($app, req).await;
+        assert_eq!(resp.status(), 302);
+        let location = resp.headers().get("Location").unwrap().to_str().unwrap().to_string();
+        let cookie = state_cookie(&resp);
+        assert_eq!(cookie.path(), Some(format!("/v1/auth/{provider}").as_str()));
+        if location.contains("nonce=") {
+            *$mock.nonce.lock().unwrap() = query_param(&location, "nonce");
+        }
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/v1/auth/{provider}/callback?code={code}&state={}", query_param(&location, "state")))
+            .cookie(cookie)
+            .to_request();
+        test::call_service($app, req).await
+    }};
+}
+
+fn logged_in_email(body: &Value) -> String {
+    decode::<Claims>(
+        body["token"].as_str().unwrap(),
+        &DecodingKey::from_secret(common::JWT_SECRET.as_bytes()),
+        &Validation::default(),
+    )
+    .unwrap()
+    .claims
+    .email
+}
+
+#[actix_rt::test]
+async fn test_login_with_each_provider_against_a_mock_server() {
+    configure_mock();
+    let (addr, mock) = mock_provider().await;
+    let app = test::init_service(
+        common::app(&Store::in_memory())
+            .app_data(web::Data::new(mock_providers(addr)))
+    ).await;
+
+    for provider in ["google", "github"] {
+        let resp = log_in!(&app, mock, provider, "good-code");
+        assert_eq!(resp.status(), 200, "login with {provider}");
+        let cleared = state_cookie(&resp);
+        assert_eq!(cleared.value(), "");
+
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(logged_in_email(&body), "admin@example.com");
+        assert!(body["refresh_token"].is_string());
+    }
+
+    let resp = log_in!(&app, mock, "github", "bad-code");
+    assert_eq!(resp.status(), 401);
+
+    let req = test::TestRequest::get().uri("/v1/auth/gitlab/login").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+}
+
+#[actix_rt::test]
+async fn test_oidc_issuer_is_configured_by_discovery_url() {
+    configure_mock();
+    let (addr, mock) = mock_provider().await;
+    // SAFETY: only this test sets the SSO variables.
+    unsafe {
+        std::env::set_var("SSO_DISCOVERY_URL", format!("http://{addr}/.well-known/openid-configuration"));
+        std::env::set_var("SSO_CLIENT_ID", "client-id");
+        std::env::set_var("SSO_CLIENT_SECRET", "client-secret");
+        std::env::set_var("SSO_REDIRECT_URI", "http://localhost:8080/v1/auth/sso/callback");
+    }
+    let sso = Oidc::from_env("sso").await.unwrap().expect("SSO is configured");
+    let app = test::init_service(
+        common::app(&Store::in_memory())
+            .app_data(web::Data::new(Providers::default().with(sso)))
+    ).await;
+
+    let req = test::TestRequest::get().uri("/v1/auth/sso/login").to_request();
+    let resp = test::call_service(&app, req).await;
+    let location = resp.headers().get("Location").unwrap().to_str().unwrap();
+    assert!(location.starts_with(&format!("http://{addr}/authorize?")));
+    assert_eq!(query_param(location, "scope"), "openid+email+profile");
+
+    let resp = log_in!(&app, mock, "sso", "good-code");
+    assert_eq!(resp.status(), 200);
+    let body: Value = test::read_body_json(resp).await;
+    assert_eq!(logged_in_email(&body), "admin@example.com");
+}
+
+#[actix_rt::test]
+async fn test_id_token_claims_are_verified() {
+    configure_mock();
+    let (addr, mock) = mock_provider().await;
+    let app = test::init_service(
+        common::app(&Store::in_memory())
+            .app_data(web::Data::new(mock_providers(addr)))
+    ).await;
+
+    for overrides in [
+        json!({ "nonce": "replayed" }),
+        json!({ "aud": "another-client" }),
+        json!({ "iss": "https://issuer.example.com" }),
+        json!({ "email_verified": false }),
+        json!({ "exp": chrono::Utc::now().timestamp() - 600 }),
+        json!({ "email": "stranger@example.com" }),
+    ] {
+        *mock.overrides.lock().unwrap() = overrides.clone();
+        let resp = log_in!(&app, mock, "google", "good-code");
+        assert_eq!(resp.status(), 401, "ID token with {overrides}");
+    }
+}
+
+#[actix_rt::test]
+async fn test_jwks_is_cached_until_the_keys_rotate() {
+    configure_mock();
+    let (addr, mock) = mock_provider().await;
+    let app = test::init_service(
+        common::app(&Store::in_memory())
+            .app_data(web::Data::new(mock_providers(addr)))
+    ).await;
+
+    for _ in 0..2 {
+        let resp = log_in!(&app, mock, "google", "good-code");
+        assert_eq!(resp.status(), 200);
+    }
+    assert_eq!(mock.jwks_fetches.load(Ordering::SeqCst), 1);
+
+    // The issuer signs with a new key the cached set does not have
+    mock.key.store(1, Ordering::SeqCst);
+    let resp = log_in!(&app, mock, "google", "good-code");
+    assert_eq!(resp.status(), 200);
+    assert_eq!(mock.jwks_fetches.load(Ordering::SeqCst), 2);
+}
+
+#[actix_rt::test]
+async fn test_cookie_mode_login_redirects_to_the_dashboard() {
+    configure_mock();
+    let (addr, mock) = mock_provider().await;
+    let app = test::init_service(
+        common::app(&Store::in_memory())
+            .app_data(web::Data::new(mock_providers(addr)))
+            .app_data(web::Data::new(SessionMode::Cookie { admin_url: "https://admin.example.com/".to_string() }))
+    ).await;
+
+    let resp = log_in!(&app, mock, "github", "good-code");
+    assert_eq!(resp.status(), 302);
+    assert_eq!(resp.headers().get("Location").unwrap(), "https://admin.example.com/");
+    assert_eq!(state_cookie(&resp).value(), "");
+
+    let session = resp.response().cookies().find(|cookie| cookie.name() == SESSION_COOKIE).unwrap();
+    assert_eq!(session.http_only(), Some(true));
+    assert_eq!(session.same_site(), Some(SameSite::Strict));
+    let token = session.value().to_string();
+    assert_eq!(logged_in_email(&json!({ "token": token })), "admin@example.com");
+    assert!(test::read_body(resp).await.is_empty());
+}
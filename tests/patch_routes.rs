@@ -0,0 +1,91 @@
+mod common;
+
+use actix_web::test;
+use serde_json::{json, Value};
+
+use portfolio_backend::repository::Store;
+
+fn patch_request(uri: &str, body: Value) -> test::TestRequest {
+    test::TestRequest::patch()
+        .uri(uri)
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", "*"))
+        .insert_header(("Content-Type", "application/merge-patch+json"))
+        .set_payload(body.to_string())
+}
+
+async fn patch_about(store: Store) {
+    let app = test::init_service(common::app(&store)).await;
+
+    let mut about = common::about("kishor.dev", "Kishor");
+    about["interests"] = json!(["rust", "music"]);
+    let req = test::TestRequest::post()
+        .uri("/v1/about")
+        .insert_header(common::bearer())
+        .set_json(about)
+        .to_request();
+    let created: Value = test::call_and_read_body_json(&app, req).await;
+    let uri = format!("/v1/about/{}", created["id"].as_str().unwrap());
+
+    let req = patch_request(&uri, json!({ "headline": "Rustacean", "location": { "city": "Bengaluru" } })).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let patched: Value = test::read_body_json(resp).await;
+    assert_eq!(patched["headline"], "Rustacean");
+    assert_eq!(patched["location"]["city"], "Bengaluru");
+    assert_eq!(patched["location"]["country"], "India");
+    assert_eq!(patched["interests"], json!(["rust", "music"]));
+
+    let req = test::TestRequest::get().uri(&uri).to_request();
+    let fetched: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(fetched["location"]["city"], "Bengaluru");
+    assert_eq!(fetched["description"], "Hello");
+
+    for body in [json!({ "name": null }), json!({ "id": "other" }), json!(["not", "an", "object"])] {
+        let req = patch_request(&uri, body).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+    }
+
+    let req = patch_request("/v1/about/missing", json!({ "name": "Nobody" })).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+}
+
+#[actix_rt::test]
+async fn test_merge_patch_with_in_memory_store() {
+    patch_about(Store::in_memory()).await;
+}
+
+#[actix_rt::test]
+async fn test_merge_patch_with_embedded_surreal() {
+    patch_about(common::surreal_store().await).await;
+}
+
+#[actix_rt::test]
+async fn test_patch_contact_and_content_type() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    let mut contact = common::contact();
+    contact["professional_email"] = json!("work@example.com");
+    let req = test::TestRequest::post()
+        .uri("/v1/contact")
+        .insert_header(common::bearer())
+        .set_json(contact)
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+    let req = patch_request("/v1/contact", json!({ "professional_email": null, "github": "kishor1445" })).to_request();
+    let patched: Value = test::call_and_read_body_json(&app, req).await;
+    assert!(patched["professional_email"].is_null());
+    assert_eq!(patched["github"], "kishor1445");
+    assert_eq!(patched["twitter"], "tw");
+
+    let req = test::TestRequest::patch()
+        .uri("/v1/contact")
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", "*"))
+        .insert_header(("Content-Type", "text/plain"))
+        .set_payload(r#"{"github":"x"}"#)
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+}
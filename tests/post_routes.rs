@@ -0,0 +1,106 @@
+mod common;
+
+use actix_web::test;
+use serde_json::{json, Value};
+
+use portfolio_backend::repository::Store;
+
+fn post(slug: &str, published_at: &str, body: &str) -> Value {
+    let mut post = common::post(slug);
+    post["published_at"] = json!(published_at);
+    post["body"] = json!(body);
+    post
+}
+
+#[actix_rt::test]
+async fn test_post_is_rendered_and_sanitized() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    let req = test::TestRequest::post()
+        .uri("/v1/posts")
+        .insert_header(common::bearer())
+        .set_json(post("hello", "2025-01-01T00:00:00Z", "# Hello\n\n<script>alert(1)</script>\n\n*world*"))
+        .to_request();
+    let created: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(created["reading_time"], 1);
+
+    let req = test::TestRequest::post()
+        .uri("/v1/posts")
+        .insert_header(common::bearer())
+        .set_json(post("hello", "2025-01-01T00:00:00Z", "again"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 409);
+
+    let req = test::TestRequest::get().uri("/v1/posts/hello").to_request();
+    let fetched: Value = test::call_and_read_body_json(&app, req).await;
+    let html = fetched["html"].as_str().unwrap();
+    assert!(html.contains("<h1>Hello</h1>"));
+    assert!(html.contains("<em>world</em>"));
+    assert!(!html.contains("<script>"));
+    assert!(fetched["body"].as_str().unwrap().starts_with("# Hello"));
+
+    let req = test::TestRequest::patch()
+        .uri("/v1/posts/hello")
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", "*"))
+        .set_json(json!({ "body": "word ".repeat(450) }))
+        .to_request();
+    let patched: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(patched["reading_time"], 3);
+}
+
+#[actix_rt::test]
+async fn test_replacing_a_post_keeps_its_date() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    let req = test::TestRequest::post()
+        .uri("/v1/posts")
+        .insert_header(common::bearer())
+        .set_json(post("hello", "2020-01-01T00:00:00Z", "text"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+    let mut replacement = common::post("hello");
+    replacement["body"] = json!("edited");
+    let req = test::TestRequest::put()
+        .uri("/v1/posts/hello")
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", "*"))
+        .set_json(replacement)
+        .to_request();
+    let replaced: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(replaced["published_at"], "2020-01-01T00:00:00Z");
+    assert_eq!(replaced["body"], "edited");
+}
+
+#[actix_rt::test]
+async fn test_posts_are_paginated_newest_first() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    for (slug, date) in [("first", "2024-01-01T00:00:00Z"), ("third", "2024-03-01T00:00:00Z"), ("second", "2024-02-01T00:00:00Z")] {
+        let req = test::TestRequest::post()
+            .uri("/v1/posts")
+            .insert_header(common::bearer())
+            .set_json(post(slug, date, "text"))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 201);
+    }
+
+    let req = test::TestRequest::get().uri("/v1/posts?limit=2").to_request();
+    let page: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(page["items"][0]["slug"], "third");
+    assert_eq!(page["items"][1]["slug"], "second");
+    assert!(page["items"][0].get("body").is_none());
+    let cursor = page["next_cursor"].as_str().unwrap().to_string();
+
+    let req = test::TestRequest::get().uri(&format!("/v1/posts?limit=2&cursor={cursor}")).to_request();
+    let page: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(page["items"][0]["slug"], "first");
+    assert!(page["next_cursor"].is_null());
+
+    let req = test::TestRequest::get().uri("/v1/posts?limit=500").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+}
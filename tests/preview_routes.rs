@@ -0,0 +1,89 @@
+mod common;
+
+use actix_web::test;
+use chrono::Duration;
+use serde_json::{json, Value};
+
+use portfolio_backend::auth::preview;
+use portfolio_backend::repository::Store;
+
+fn draft(slug: &str) -> Value {
+    let mut project = common::project(slug);
+    project["status"] = json!("draft");
+    project
+}
+
+#[actix_rt::test]
+async fn test_preview_token_unlocks_one_draft() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    for slug in ["secret", "other-secret"] {
+        let req = test::TestRequest::post()
+            .uri("/v1/projects")
+            .insert_header(common::bearer())
+            .set_json(draft(slug))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 201);
+    }
+
+    let req = test::TestRequest::post().uri("/v1/projects/secret/preview").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 401);
+
+    let req = test::TestRequest::post()
+        .uri("/v1/projects/secret/preview")
+        .insert_header(common::bearer())
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let minted: Value = test::read_body_json(resp).await;
+    let token = minted["token"].as_str().unwrap().to_string();
+    assert!(minted["expires_at"].is_string());
+
+    let req = test::TestRequest::get().uri("/v1/projects/secret").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/v1/projects/secret?preview={token}"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers().get("Cache-Control").unwrap(), "no-store");
+    let project: Value = test::read_body_json(resp).await;
+    assert_eq!(project["status"], "draft");
+
+    // The token is scoped to the record it was minted for
+    let req = test::TestRequest::get()
+        .uri(&format!("/v1/projects/other-secret?preview={token}"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    // and it is not a login
+    let req = test::TestRequest::post()
+        .uri("/v1/projects/secret/publish")
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 401);
+}
+
+#[actix_rt::test]
+async fn test_invalid_preview_tokens_are_rejected() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    let req = test::TestRequest::post()
+        .uri("/v1/projects")
+        .insert_header(common::bearer())
+        .set_json(draft("secret"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+    let expired = preview::mint("projects", "secret", Duration::minutes(-5)).unwrap().token;
+    let login = common::admin_token();
+    for token in [expired.as_str(), login.as_str(), "garbage"] {
+        let req = test::TestRequest::get()
+            .uri(&format!("/v1/projects/secret?preview={token}"))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 401);
+    }
+}
@@ -0,0 +1,135 @@
+mod common;
+
+use actix_web::test;
+use serde_json::{json, Value};
+
+use portfolio_backend::repository::Store;
+
+fn project(slug: &str) -> Value {
+    let mut project = common::project(slug);
+    project["repo_url"] = json!("https://github.com/kishor1445/portfolio-backend-rs");
+    project["featured"] = json!(true);
+    project
+}
+
+#[actix_rt::test]
+async fn test_project_is_addressed_by_slug() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    let req = test::TestRequest::post()
+        .uri("/v1/tech-stacks")
+        .insert_header(common::bearer())
+        .set_json(common::tech_stack("Rust"))
+        .to_request();
+    let created: Value = test::call_and_read_body_json(&app, req).await;
+    let rust = created["id"].as_str().unwrap().to_string();
+
+    let mut payload = project("portfolio-backend");
+    payload["tech_stacks"] = json!([rust]);
+    let req = test::TestRequest::post()
+        .uri("/v1/projects")
+        .insert_header(common::bearer())
+        .set_json(payload)
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+    let req = test::TestRequest::post()
+        .uri("/v1/projects")
+        .insert_header(common::bearer())
+        .set_json(project("portfolio-backend"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 409);
+
+    let req = test::TestRequest::get().uri("/v1/projects/portfolio-backend").to_request();
+    let fetched: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(fetched["title"], "Portfolio Backend");
+    assert_eq!(fetched["featured"], true);
+
+    let req = test::TestRequest::get().uri("/v1/projects").to_request();
+    let page: Value = test::call_and_read_body_json(&app, req).await;
+    let list = page["items"].as_array().unwrap();
+    assert_eq!(list.len(), 1);
+
+    let req = test::TestRequest::put()
+        .uri("/v1/projects/portfolio-backend")
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", "*"))
+        .set_json(project("renamed"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+    let req = test::TestRequest::delete()
+        .uri("/v1/projects/portfolio-backend")
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", "*"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+}
+
+#[actix_rt::test]
+async fn test_project_slug_is_validated() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    let req = test::TestRequest::post()
+        .uri("/v1/projects")
+        .insert_header(common::bearer())
+        .set_json(project("Not A Slug"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+}
+
+#[actix_rt::test]
+async fn test_project_tech_stacks_must_exist() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    let req = test::TestRequest::post()
+        .uri("/v1/tech-stacks")
+        .insert_header(common::bearer())
+        .set_json(common::tech_stack("Rust"))
+        .to_request();
+    let created: Value = test::call_and_read_body_json(&app, req).await;
+    let rust = created["id"].as_str().unwrap().to_string();
+
+    let mut payload = project("portfolio-backend");
+    payload["tech_stacks"] = json!([rust, "missing"]);
+    let req = test::TestRequest::post()
+        .uri("/v1/projects")
+        .insert_header(common::bearer())
+        .set_json(&payload)
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+    payload["tech_stacks"] = json!([rust]);
+    let req = test::TestRequest::post()
+        .uri("/v1/projects")
+        .insert_header(common::bearer())
+        .set_json(&payload)
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+    let req = test::TestRequest::patch()
+        .uri("/v1/projects/portfolio-backend")
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", "*"))
+        .set_json(json!({ "tech_stacks": ["missing"] }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/v1/tech-stacks/{rust}"))
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", "*"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+    let req = test::TestRequest::put()
+        .uri("/v1/projects/portfolio-backend")
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", "*"))
+        .set_json(&payload)
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+}
@@ -0,0 +1,180 @@
+mod common;
+
+use actix_web::test;
+use serde_json::{json, Value};
+
+use portfolio_backend::repository::Store;
+
+fn certificate(title: &str, status: Option<&str>) -> Value {
+    let mut certificate = common::certificate(title);
+    match status {
+        Some(status) => certificate["status"] = json!(status),
+        None => {
+            certificate.as_object_mut().unwrap().remove("status");
+        }
+    }
+    certificate
+}
+
+#[actix_rt::test]
+async fn test_replacing_without_a_status_keeps_it() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    let req = test::TestRequest::post()
+        .uri("/v1/certificates")
+        .insert_header(common::bearer())
+        .set_json(certificate("Live", Some("published")))
+        .to_request();
+    let live: Value = test::call_and_read_body_json(&app, req).await;
+    let id = live["id"].as_str().unwrap();
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/v1/certificates/{id}"))
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", "*"))
+        .set_json(certificate("Still live", None))
+        .to_request();
+    let replaced: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(replaced["status"], "published");
+
+    let req = test::TestRequest::get().uri(&format!("/v1/certificates/{id}")).to_request();
+    let current: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(current["title"], "Still live");
+}
+
+#[actix_rt::test]
+async fn test_drafts_are_hidden_from_the_public() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    let req = test::TestRequest::post()
+        .uri("/v1/certificates")
+        .insert_header(common::bearer())
+        .set_json(certificate("Live", Some("published")))
+        .to_request();
+    let live: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(live["status"], "published");
+
+    // Without a status a new record is a draft
+    let req = test::TestRequest::post()
+        .uri("/v1/certificates")
+        .insert_header(common::bearer())
+        .set_json(certificate("Staged", None))
+        .to_request();
+    let draft: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(draft["status"], "draft");
+    let id = draft["id"].as_str().unwrap().to_string();
+
+    let req = test::TestRequest::get().uri("/v1/certificates/all").to_request();
+    let page: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(page["items"].as_array().unwrap().len(), 1);
+    assert_eq!(page["items"][0]["title"], "Live");
+
+    let req = test::TestRequest::get().uri(&format!("/v1/certificates/{id}")).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    // Admins see drafts, and those responses are never cached
+    let req = test::TestRequest::get()
+        .uri("/v1/certificates/all")
+        .insert_header(common::bearer())
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.headers().get("Cache-Control").unwrap(), "no-store");
+    let page: Value = test::read_body_json(resp).await;
+    assert_eq!(page["items"].as_array().unwrap().len(), 2);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/v1/certificates/{id}"))
+        .insert_header(common::bearer())
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    let req = test::TestRequest::post().uri(&format!("/v1/certificates/{id}/publish")).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 401);
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/v1/certificates/{id}/publish"))
+        .insert_header(common::bearer())
+        .to_request();
+    let published: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(published["status"], "published");
+
+    let req = test::TestRequest::get().uri(&format!("/v1/certificates/{id}")).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/v1/certificates/{id}/unpublish"))
+        .insert_header(common::bearer())
+        .to_request();
+    let unpublished: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(unpublished["status"], "draft");
+
+    let req = test::TestRequest::get().uri(&format!("/v1/certificates/{id}")).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    // Archived records are hidden too
+    let live_id = live["id"].as_str().unwrap();
+    let req = test::TestRequest::patch()
+        .uri(&format!("/v1/certificates/{live_id}"))
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", "*"))
+        .set_json(json!({ "status": "archived" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    let req = test::TestRequest::get().uri("/v1/certificates/all").to_request();
+    let page: Value = test::call_and_read_body_json(&app, req).await;
+    assert!(page["items"].as_array().unwrap().is_empty());
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/v1/certificates/{id}/revisions"))
+        .insert_header(common::bearer())
+        .to_request();
+    let history: Vec<Value> = test::call_and_read_body_json(&app, req).await;
+    let actions: Vec<&str> = history.iter().map(|r| r["action"].as_str().unwrap()).collect();
+    assert_eq!(actions, ["unpublish", "publish", "create"]);
+}
+
+#[actix_rt::test]
+async fn test_draft_posts_stay_out_of_feeds() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    for (slug, status) in [("out-now", "published"), ("coming-soon", "draft")] {
+        let req = test::TestRequest::post()
+            .uri("/v1/posts")
+            .insert_header(common::bearer())
+            .set_json(json!({ "slug": slug, "title": slug, "body": "Hello", "status": status }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 201);
+    }
+
+    let req = test::TestRequest::get().uri("/feed.xml").to_request();
+    let body = String::from_utf8(test::call_and_read_body(&app, req).await.to_vec()).unwrap();
+    assert!(body.contains("out-now"));
+    assert!(!body.contains("coming-soon"));
+
+    let req = test::TestRequest::get().uri("/v1/posts").to_request();
+    let page: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(page["items"].as_array().unwrap().len(), 1);
+
+    let req = test::TestRequest::get().uri("/v1/posts/coming-soon").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    let req = test::TestRequest::get()
+        .uri("/v1/posts/coming-soon")
+        .insert_header(common::bearer())
+        .to_request();
+    let draft: Value = test::call_and_read_body_json(&app, req).await;
+    assert!(draft.get("published_at").is_none());
+
+    // Publishing dates the post
+    let req = test::TestRequest::post()
+        .uri("/v1/posts/coming-soon/publish")
+        .insert_header(common::bearer())
+        .to_request();
+    let published: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(published["status"], "published");
+    assert!(published["published_at"].is_string());
+}
@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use portfolio_backend::models::Record;
+use portfolio_backend::models::about::{RecordIdReturn, TechStack};
+use portfolio_backend::models::session::RevokedToken;
+use portfolio_backend::repository::{ReadCache, Store};
+
+fn tech_stack(name: &str) -> TechStack {
+    TechStack {
+        id: None,
+        name: name.to_string(),
+        description: None,
+        logo: None,
+        tags: vec![],
+        position: None,
+        status: Default::default(),
+        deleted_at: None,
+    }
+}
+
+#[actix_rt::test]
+async fn test_reads_are_cached_until_a_write() {
+    let backing = Store::in_memory();
+    let cached = backing.clone().cached(ReadCache::new(Duration::from_secs(60), 100)).repository::<TechStack>();
+    let direct = backing.repository::<TechStack>();
+
+    assert!(cached.list().await.unwrap().is_empty());
+
+    // Writes that bypass the cache are not seen until the entry is invalidated
+    direct.create(tech_stack("Rust")).await.unwrap();
+    assert!(cached.list().await.unwrap().is_empty());
+
+    let created = cached.create(tech_stack("Go")).await.unwrap();
+    let id = created.id().unwrap().to_string();
+    assert_eq!(cached.list().await.unwrap().len(), 2);
+
+    assert_eq!(cached.get(&id).await.unwrap().unwrap().name, "Go");
+    direct.update(&id, tech_stack("Zig")).await.unwrap();
+    assert_eq!(cached.get(&id).await.unwrap().unwrap().name, "Go");
+
+    cached.delete(&id).await.unwrap();
+    assert!(cached.get(&id).await.unwrap().is_none());
+    assert_eq!(cached.list().await.unwrap().len(), 1);
+}
+
+#[actix_rt::test]
+async fn test_cache_entries_expire() {
+    let backing = Store::in_memory();
+    let cached = backing.clone().cached(ReadCache::new(Duration::from_millis(20), 100)).repository::<TechStack>();
+    let direct = backing.repository::<TechStack>();
+
+    assert!(cached.list().await.unwrap().is_empty());
+    direct.create(tech_stack("Rust")).await.unwrap();
+    assert!(cached.list().await.unwrap().is_empty());
+
+    actix_rt::time::sleep(Duration::from_millis(40)).await;
+    assert_eq!(cached.list().await.unwrap().len(), 1);
+}
+
+#[actix_rt::test]
+async fn test_cache_evicts_when_full() {
+    let backing = Store::in_memory();
+    let cached = backing.clone().cached(ReadCache::new(Duration::from_secs(60), 1)).repository::<TechStack>();
+    let direct = backing.repository::<TechStack>();
+
+    assert!(cached.list().await.unwrap().is_empty());
+    // Caching a record evicts the listing
+    assert!(cached.get("missing").await.unwrap().is_none());
+    direct.create(tech_stack("Rust")).await.unwrap();
+    assert_eq!(cached.list().await.unwrap().len(), 1);
+}
+
+#[actix_rt::test]
+async fn test_revoked_tokens_are_never_cached() {
+    let backing = Store::in_memory();
+    let cached = backing.clone().cached(ReadCache::new(Duration::from_secs(60), 100)).repository::<RevokedToken>();
+    let direct = backing.repository::<RevokedToken>();
+
+    assert!(cached.get("jti").await.unwrap().is_none());
+    direct
+        .create(RevokedToken {
+            id: Some(RecordIdReturn("jti".to_string())),
+            expires_at: Utc::now(),
+        })
+        .await
+        .unwrap();
+    assert!(cached.get("jti").await.unwrap().is_some());
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use actix_web::test;
+use serde_json::{json, Value};
+
+use portfolio_backend::repository::Store;
+
+async fn reorder_tech_stacks(store: Store) {
+    let app = test::init_service(common::app(&store)).await;
+
+    let mut ids = Vec::new();
+    for name in ["Rust", "Go", "Python"] {
+        let req = test::TestRequest::post()
+            .uri("/v1/tech-stacks")
+            .insert_header(common::bearer())
+            .set_json(common::tech_stack(name))
+            .to_request();
+        let created: Value = test::call_and_read_body_json(&app, req).await;
+        ids.push(created["id"].as_str().unwrap().to_string());
+    }
+
+    let order = [ids[2].clone(), ids[0].clone(), ids[1].clone()];
+    let req = test::TestRequest::post()
+        .uri("/v1/tech-stacks/reorder")
+        .insert_header(common::bearer())
+        .set_json(json!({ "ids": order }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+    let req = test::TestRequest::get().uri("/v1/tech-stacks").to_request();
+    let page: Value = test::call_and_read_body_json(&app, req).await;
+    let names: Vec<&str> = page["items"].as_array().unwrap().iter().map(|t| t["name"].as_str().unwrap()).collect();
+    assert_eq!(names, ["Python", "Rust", "Go"]);
+    assert_eq!(page["items"][0]["position"], 0);
+
+    // A replacement that leaves the position out keeps it
+    let req = test::TestRequest::put()
+        .uri(&format!("/v1/tech-stacks/{}", ids[0]))
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", "*"))
+        .set_json(common::tech_stack("Rust"))
+        .to_request();
+    let replaced: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(replaced["position"], 1);
+
+    for body in [
+        json!({ "ids": [ids[0], ids[1]] }),
+        json!({ "ids": [ids[0], ids[0], ids[1]] }),
+        json!({ "ids": [ids[0], ids[1], "missing"] }),
+    ] {
+        let req = test::TestRequest::post()
+            .uri("/v1/tech-stacks/reorder")
+            .insert_header(common::bearer())
+            .set_json(body)
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+    }
+
+    let req = test::TestRequest::post()
+        .uri("/v1/tech-stacks/reorder")
+        .set_json(json!({ "ids": order }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 401);
+}
+
+#[actix_rt::test]
+async fn test_reorder_with_in_memory_store() {
+    reorder_tech_stacks(Store::in_memory()).await;
+}
+
+#[actix_rt::test]
+async fn test_reorder_with_embedded_surreal() {
+    reorder_tech_stacks(common::surreal_store().await).await;
+}
+
+#[actix_rt::test]
+async fn test_reorder_is_only_registered_for_orderable_resources() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    let req = test::TestRequest::post()
+        .uri("/v1/experience/reorder")
+        .insert_header(common::bearer())
+        .set_json(json!({ "ids": [] }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+}
@@ -0,0 +1,238 @@
+mod common;
+
+use actix_web::test;
+use serde_json::{json, Value};
+
+use portfolio_backend::models::revision::Revision;
+use portfolio_backend::repository::Store;
+
+async fn exercise_revisions(store: Store) {
+    let app = test::init_service(common::app(&store)).await;
+
+    let req = test::TestRequest::post()
+        .uri("/v1/tech-stacks")
+        .insert_header(common::bearer())
+        .set_json(common::tech_stack("Rust"))
+        .to_request();
+    let created: Value = test::call_and_read_body_json(&app, req).await;
+    let id = created["id"].as_str().unwrap().to_string();
+
+    let req = test::TestRequest::patch()
+        .uri(&format!("/v1/tech-stacks/{id}"))
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", "*"))
+        .set_json(json!({ "name": "Rustlang", "description": "Fearless concurrency" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/v1/tech-stacks/{id}"))
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", "*"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+    // History is for admins only
+    let req = test::TestRequest::get().uri(&format!("/v1/tech-stacks/{id}/revisions")).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 401);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/v1/tech-stacks/{id}/revisions"))
+        .insert_header(common::bearer())
+        .to_request();
+    let history: Vec<Value> = test::call_and_read_body_json(&app, req).await;
+    let actions: Vec<&str> = history.iter().map(|r| r["action"].as_str().unwrap()).collect();
+    assert_eq!(actions, ["delete", "update", "create"]);
+    assert!(history.iter().all(|r| r["author"] == "admin@example.com"));
+    assert_eq!(history[2]["document"], Value::Null);
+    assert_eq!(history[1]["document"]["name"], "Rust");
+    assert_eq!(history[0]["document"]["name"], "Rustlang");
+
+    let (deleted, updated) = (
+        history[0]["id"].as_str().unwrap().to_string(),
+        history[1]["id"].as_str().unwrap().to_string(),
+    );
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/v1/tech-stacks/{id}/revisions/diff?from={updated}&to={deleted}"))
+        .insert_header(common::bearer())
+        .to_request();
+    let patch: Value = test::call_and_read_body_json(&app, req).await;
+    // Diffs are between stored documents, where localized text is kept per locale
+    assert_eq!(patch, json!({ "name": "Rustlang", "description": { "en": "Fearless concurrency" } }));
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/v1/tech-stacks/{id}/revisions/{updated}/restore"))
+        .insert_header(common::bearer())
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let restored: Value = test::read_body_json(resp).await;
+    assert_eq!(restored["id"], id.as_str());
+    assert_eq!(restored["name"], "Rust");
+    assert_eq!(restored["description"], Value::Null);
+
+    let req = test::TestRequest::get().uri(&format!("/v1/tech-stacks/{id}")).to_request();
+    let current: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(current["name"], "Rust");
+
+    // Nothing changed since the restore, so the diff against the current record is empty
+    let req = test::TestRequest::get()
+        .uri(&format!("/v1/tech-stacks/{id}/revisions/diff?from={updated}"))
+        .insert_header(common::bearer())
+        .to_request();
+    let patch: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(patch, json!({}));
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/v1/tech-stacks/{id}/revisions"))
+        .insert_header(common::bearer())
+        .to_request();
+    let history: Vec<Value> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(history.len(), 4);
+    assert_eq!(history[0]["action"], "restore");
+
+    // A creation has no previous document to go back to
+    let created_rev = history[3]["id"].as_str().unwrap();
+    let req = test::TestRequest::post()
+        .uri(&format!("/v1/tech-stacks/{id}/revisions/{created_rev}/restore"))
+        .insert_header(common::bearer())
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+    // Revisions are scoped to their record
+    let req = test::TestRequest::post()
+        .uri(&format!("/v1/certificates/{id}/revisions/{updated}/restore"))
+        .insert_header(common::bearer())
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    // The last write to a collection is read without loading its history
+    let revisions = store.repository::<Revision>();
+    let newest = revisions
+        .find(&[("collection", "tech_stacks")])
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|revision| revision.created_at)
+        .max();
+    assert!(newest.is_some());
+    assert_eq!(revisions.last_created(&[("collection", "tech_stacks")]).await.unwrap(), newest);
+    assert_eq!(revisions.last_created(&[("collection", "posts")]).await.unwrap(), None);
+}
+
+#[actix_rt::test]
+async fn test_revisions_in_memory() {
+    exercise_revisions(Store::in_memory()).await;
+}
+
+#[actix_rt::test]
+async fn test_revisions_in_surreal() {
+    exercise_revisions(common::surreal_store().await).await;
+}
+
+async fn shared_records_have_one_history(store: Store) {
+    let app = test::init_service(common::app(&store)).await;
+
+    let mut abouts = Vec::new();
+    for (domain, name) in [("a.dev", "A"), ("b.dev", "B")] {
+        let req = test::TestRequest::post()
+            .uri("/v1/about")
+            .insert_header(common::bearer())
+            .set_json(common::about(domain, name))
+            .to_request();
+        let about: Value = test::call_and_read_body_json(&app, req).await;
+        abouts.push(about["id"].as_str().unwrap().to_string());
+    }
+
+    // About records are shared, so writing one through another site adds to the same history
+    let req = test::TestRequest::patch()
+        .uri(&format!("/v1/about/{}", abouts[0]))
+        .insert_header(common::bearer())
+        .insert_header(("X-Site", "b.dev"))
+        .insert_header(("If-Match", "*"))
+        .set_json(json!({ "name": "Renamed" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    let mut histories = Vec::new();
+    for site in ["a.dev", "b.dev"] {
+        let req = test::TestRequest::get()
+            .uri(&format!("/v1/about/{}/revisions", abouts[0]))
+            .insert_header(common::bearer())
+            .insert_header(("X-Site", site))
+            .to_request();
+        let history: Vec<Value> = test::call_and_read_body_json(&app, req).await;
+        let actions: Vec<&str> = history.iter().map(|revision| revision["action"].as_str().unwrap()).collect();
+        assert_eq!(actions, ["update", "create"], "{site}");
+        histories.push(history);
+    }
+    assert_eq!(histories[0], histories[1]);
+
+    let req = test::TestRequest::post()
+        .uri(&format!(
+            "/v1/about/{}/revisions/{}/restore",
+            abouts[0],
+            histories[0][0]["id"].as_str().unwrap()
+        ))
+        .insert_header(common::bearer())
+        .insert_header(("X-Site", "a.dev"))
+        .to_request();
+    let restored: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(restored["name"], "A");
+}
+
+#[actix_rt::test]
+async fn test_shared_records_have_one_history_in_memory() {
+    shared_records_have_one_history(Store::in_memory()).await;
+}
+
+#[actix_rt::test]
+async fn test_shared_records_have_one_history_in_surreal() {
+    shared_records_have_one_history(common::surreal_store().await).await;
+}
+
+#[actix_rt::test]
+async fn test_contact_revisions() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    let req = test::TestRequest::post()
+        .uri("/v1/contact")
+        .insert_header(common::bearer())
+        .set_json(common::contact())
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+    let req = test::TestRequest::get()
+        .uri("/v1/contact/revisions")
+        .insert_header(common::bearer())
+        .to_request();
+    let history: Vec<Value> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0]["record"], "default");
+    assert_eq!(history[0]["collection"], "contact");
+}
+
+#[actix_rt::test]
+async fn test_failed_write_records_no_revision_in_surreal() {
+    let store = common::surreal_store().await;
+    let app = test::init_service(common::app(&store)).await;
+
+    for expected in [201, 409] {
+        let req = test::TestRequest::post()
+            .uri("/v1/projects")
+            .insert_header(common::bearer())
+            .set_json(common::project("portfolio-backend"))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), expected);
+    }
+
+    let req = test::TestRequest::get()
+        .uri("/v1/projects/portfolio-backend/revisions")
+        .insert_header(common::bearer())
+        .to_request();
+    let history: Vec<Value> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0]["action"], "create");
+}
@@ -0,0 +1,125 @@
+mod common;
+
+use std::sync::Arc;
+
+use actix_web::test;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde_json::{json, Value};
+
+use portfolio_backend::clock::MockClock;
+use portfolio_backend::repository::Store;
+
+fn start() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2030, 1, 1, 9, 0, 0).unwrap()
+}
+
+fn post(slug: &str, publish_at: DateTime<Utc>, unpublish_at: Option<DateTime<Utc>>) -> Value {
+    let mut post = common::post(slug);
+    post["status"] = json!("draft");
+    post["publish_at"] = json!(publish_at);
+    post["unpublish_at"] = json!(unpublish_at);
+    post
+}
+
+#[actix_rt::test]
+async fn test_scheduler_publishes_and_unpublishes_on_time() {
+    let store = Store::in_memory();
+    let clock = MockClock::new(start());
+    let scheduler = store.scheduler(Arc::new(clock.clone()));
+    let app = test::init_service(common::app(&store)).await;
+
+    let req = test::TestRequest::post()
+        .uri("/v1/posts")
+        .insert_header(common::bearer())
+        .set_json(post("launch", start() + Duration::hours(1), Some(start() + Duration::hours(3))))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+    let mut certificate = common::certificate("Certified Rustacean");
+    certificate["status"] = json!("draft");
+    certificate["publish_at"] = json!(start() + Duration::hours(2));
+    let req = test::TestRequest::post()
+        .uri("/v1/certificates")
+        .insert_header(common::bearer())
+        .set_json(certificate)
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+    assert_eq!(scheduler.tick().await.unwrap(), 0);
+
+    clock.advance(Duration::hours(1));
+    assert_eq!(scheduler.tick().await.unwrap(), 1);
+    let req = test::TestRequest::get().uri("/v1/posts/launch").to_request();
+    let launched: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(launched["status"], "published");
+    assert!(launched.get("publish_at").is_none());
+    // Dated by when it went live, not by when the draft was written
+    assert_eq!(launched["published_at"], json!(start() + Duration::hours(1)));
+    let req = test::TestRequest::get().uri("/feed.xml").to_request();
+    let body = String::from_utf8(test::call_and_read_body(&app, req).await.to_vec()).unwrap();
+    assert!(body.contains("<pubDate>Tue, 1 Jan 2030 10:00:00 +0000</pubDate>"));
+    let req = test::TestRequest::get().uri("/v1/certificates/all").to_request();
+    let page: Value = test::call_and_read_body_json(&app, req).await;
+    assert!(page["items"].as_array().unwrap().is_empty());
+
+    // Nothing is applied twice
+    assert_eq!(scheduler.tick().await.unwrap(), 0);
+
+    clock.advance(Duration::hours(2));
+    assert_eq!(scheduler.tick().await.unwrap(), 2);
+    let req = test::TestRequest::get().uri("/v1/posts/launch").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+    let req = test::TestRequest::get().uri("/v1/certificates/all").to_request();
+    let page: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(page["items"].as_array().unwrap().len(), 1);
+
+    let req = test::TestRequest::get()
+        .uri("/v1/posts/launch/revisions")
+        .insert_header(common::bearer())
+        .to_request();
+    let history: Vec<Value> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(history[0]["action"], "unpublish");
+    assert_eq!(history[0]["author"], "scheduler");
+    assert_eq!(history[1]["action"], "publish");
+}
+
+#[actix_rt::test]
+async fn test_schedule_must_end_after_it_starts() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    let req = test::TestRequest::post()
+        .uri("/v1/posts")
+        .insert_header(common::bearer())
+        .set_json(post("backwards", start(), Some(start() - Duration::hours(1))))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+}
+
+#[actix_rt::test]
+async fn test_schedule_survives_a_restart() {
+    let db = common::surreal_db().await;
+    let clock = MockClock::new(start());
+
+    {
+        let store = Store::surreal(db.clone());
+        let app = test::init_service(common::app(&store)).await;
+        let req = test::TestRequest::post()
+            .uri("/v1/posts")
+            .insert_header(common::bearer())
+            .set_json(post("later", start() + Duration::minutes(30), None))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 201);
+    }
+
+    // A scheduler started afterwards finds the schedule in the database
+    let store = Store::surreal(db);
+    let scheduler = store.scheduler(Arc::new(clock.clone()));
+    clock.advance(Duration::hours(1));
+    assert_eq!(scheduler.tick().await.unwrap(), 1);
+
+    let app = test::init_service(common::app(&store)).await;
+    let req = test::TestRequest::get().uri("/v1/posts/later").to_request();
+    let later: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(later["status"], "published");
+}
@@ -0,0 +1,234 @@
+mod common;
+
+use actix_web::cookie::{Cookie, SameSite};
+use actix_web::test;
+use serde_json::{json, Value};
+
+use portfolio_backend::auth::oauth::UserProfile;
+use portfolio_backend::auth::session::{self, CSRF_COOKIE, CSRF_HEADER, REFRESH_COOKIE, SESSION_COOKIE};
+use portfolio_backend::models::session::Session;
+use portfolio_backend::repository::Store;
+use portfolio_backend::services::session_service::{self, SessionTokens};
+
+async fn log_in(store: &Store) -> SessionTokens {
+    common::admin_token();
+    let profile = UserProfile {
+        email: "admin@example.com".to_string(),
+        name: "Admin".to_string(),
+        picture: String::new(),
+    };
+    session_service::start(store, profile).await.expect("session starts")
+}
+
+fn bearer(token: &str) -> (&'static str, String) {
+    ("Authorization", format!("Bearer {token}"))
+}
+
+/// Whether `token` is still accepted on an admin endpoint.
+macro_rules! accepted {
+    ($app:expr, $token:expr) => {{
+        let req = test::TestRequest::get().uri("/v1/trash").insert_header(bearer($token)).to_request();
+        test::call_service($app, req).await.status() == 200
+    }};
+}
+
+macro_rules! refresh {
+    ($app:expr, $refresh_token:expr) => {{
+        let req = test::TestRequest::post()
+            .uri("/v1/auth/refresh")
+            .set_json(json!({ "refresh_token": $refresh_token }))
+            .to_request();
+        test::call_service($app, req).await
+    }};
+}
+
+async fn exercise_sessions(store: Store) {
+    let app = test::init_service(common::app(&store)).await;
+
+    let first = log_in(&store).await;
+    assert!(accepted!(&app, &first.token));
+
+    // A wrong secret is rejected without ending the session
+    assert_eq!(refresh!(&app, format!("{}.forged", first.session_id)).status(), 401);
+    assert!(accepted!(&app, &first.token));
+
+    // Rotation only replaces the session while it has the hash it was read with
+    let sessions = store.repository::<Session>();
+    let stored = sessions.get(&first.session_id).await.unwrap().unwrap();
+    let stale = sessions.update_if(&first.session_id, "refresh_hash", "stale", stored).await.unwrap();
+    assert!(stale.is_none());
+
+    // Refreshing rotates both tokens and revokes the previous access token
+    let resp = refresh!(&app, &first.refresh_token);
+    assert_eq!(resp.status(), 200);
+    let second: Value = test::read_body_json(resp).await;
+    let (token, refresh_token) = (second["token"].as_str().unwrap(), second["refresh_token"].as_str().unwrap());
+    assert_ne!(refresh_token, first.refresh_token);
+    assert!(second["expires_at"].is_string());
+    assert!(accepted!(&app, token));
+    assert!(!accepted!(&app, &first.token));
+
+    // Replaying a used refresh token revokes the session it belonged to
+    let resp = refresh!(&app, &first.refresh_token);
+    assert_eq!(resp.status(), 401);
+    assert!(!accepted!(&app, token));
+    assert_eq!(refresh!(&app, refresh_token).status(), 401);
+
+    // Logging out ends the session
+    let session = log_in(&store).await;
+    let req = test::TestRequest::post().uri("/v1/auth/logout").insert_header(bearer(&session.token)).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+    assert!(!accepted!(&app, &session.token));
+    assert_eq!(refresh!(&app, &session.refresh_token).status(), 401);
+
+    // So does sending the refresh token, once the access token expired
+    let session = log_in(&store).await;
+    let req = test::TestRequest::post()
+        .uri("/v1/auth/logout")
+        .set_json(json!({ "refresh_token": session.refresh_token }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+    assert!(!accepted!(&app, &session.token));
+    assert_eq!(refresh!(&app, &session.refresh_token).status(), 401);
+
+    // Revoking every session logs out the other devices too
+    let (laptop, phone) = (log_in(&store).await, log_in(&store).await);
+    let req = test::TestRequest::delete().uri("/v1/auth/sessions").insert_header(bearer(&laptop.token)).to_request();
+    let revoked: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(revoked, json!({ "revoked": 2 }));
+    for session in [&laptop, &phone] {
+        assert!(!accepted!(&app, &session.token));
+        assert_eq!(refresh!(&app, &session.refresh_token).status(), 401);
+    }
+
+    assert_eq!(refresh!(&app, "not-a-session.secret").status(), 401);
+    assert_eq!(refresh!(&app, "malformed").status(), 401);
+}
+
+#[actix_rt::test]
+async fn test_sessions_in_memory() {
+    exercise_sessions(Store::in_memory()).await;
+}
+
+#[actix_rt::test]
+async fn test_sessions_in_surreal() {
+    exercise_sessions(common::surreal_store().await).await;
+}
+
+#[actix_rt::test]
+async fn test_logout_requires_a_token() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    let req = test::TestRequest::post().uri("/v1/auth/logout").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 401);
+}
+
+fn set_cookie<'a>(resp: &'a actix_web::dev::ServiceResponse, name: &str) -> Option<Cookie<'a>> {
+    resp.response().cookies().find(|cookie| cookie.name() == name)
+}
+
+#[actix_rt::test]
+async fn test_cookie_sessions_need_the_csrf_token_for_writes() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+    let tokens = log_in(&store).await;
+    let cookies = session::session_cookies(&tokens).unwrap();
+    let [access, refresh_token, csrf] = [SESSION_COOKIE, REFRESH_COOKIE, CSRF_COOKIE]
+        .map(|name| cookies.iter().find(|cookie| cookie.name() == name).unwrap().clone());
+    assert!(access.http_only().unwrap() && refresh_token.http_only().unwrap());
+    assert_eq!(csrf.http_only(), Some(false), "the dashboard reads the CSRF token");
+    assert_eq!(refresh_token.path(), Some("/v1/auth"));
+    for cookie in &cookies {
+        assert_eq!(cookie.secure(), Some(true));
+        assert_eq!(cookie.same_site(), Some(SameSite::Strict));
+    }
+
+    // Reads need only the cookie
+    let req = test::TestRequest::get().uri("/v1/trash").cookie(access.clone()).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    // Writes need the CSRF token too
+    for header in [None, Some("forged")] {
+        let mut req = test::TestRequest::post().uri("/v1/tech-stacks").cookie(access.clone()).set_json(common::tech_stack("Rust"));
+        if let Some(header) = header {
+            req = req.insert_header((CSRF_HEADER, header));
+        }
+        assert_eq!(test::call_service(&app, req.to_request()).await.status(), 403);
+    }
+    // ...and must come from the API's own origin
+    let req = test::TestRequest::post()
+        .uri("/v1/tech-stacks")
+        .cookie(access.clone())
+        .insert_header((CSRF_HEADER, csrf.value()))
+        .insert_header(("Origin", "https://evil.example.com"))
+        .set_json(common::tech_stack("Rust"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 403);
+    let req = test::TestRequest::post()
+        .uri("/v1/tech-stacks")
+        .cookie(access.clone())
+        .insert_header((CSRF_HEADER, csrf.value()))
+        .insert_header(("Origin", "http://localhost:8080"))
+        .set_json(common::tech_stack("Rust"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+    // So does refreshing from the cookie, which sets the new tokens as cookies
+    let req = test::TestRequest::post().uri("/v1/auth/refresh").cookie(refresh_token.clone()).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 403);
+    let req = test::TestRequest::post()
+        .uri("/v1/auth/refresh")
+        .cookie(refresh_token)
+        .insert_header((CSRF_HEADER, csrf.value()))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 204);
+    let renewed = set_cookie(&resp, SESSION_COOKIE).unwrap().into_owned();
+    assert_eq!(set_cookie(&resp, CSRF_COOKIE).unwrap().value(), csrf.value(), "the CSRF token lasts the session");
+    assert!(set_cookie(&resp, REFRESH_COOKIE).is_some());
+
+    let req = test::TestRequest::get().uri("/v1/trash").cookie(access).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 401);
+
+    // Logging out expires the cookies
+    let req = test::TestRequest::post()
+        .uri("/v1/auth/logout")
+        .cookie(renewed.clone())
+        .insert_header((CSRF_HEADER, csrf.value()))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 204);
+    assert_eq!(set_cookie(&resp, SESSION_COOKIE).unwrap().value(), "");
+    let req = test::TestRequest::get().uri("/v1/trash").cookie(renewed).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 401);
+
+    // The refresh cookie alone logs out too, with the CSRF token
+    let tokens = log_in(&store).await;
+    let cookies = session::session_cookies(&tokens).unwrap();
+    let [refresh_token, csrf] = [REFRESH_COOKIE, CSRF_COOKIE]
+        .map(|name| cookies.iter().find(|cookie| cookie.name() == name).unwrap().clone());
+    let req = test::TestRequest::post().uri("/v1/auth/logout").cookie(refresh_token.clone()).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 403);
+    let req = test::TestRequest::post()
+        .uri("/v1/auth/logout")
+        .cookie(refresh_token)
+        .insert_header((CSRF_HEADER, csrf.value()))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+    assert!(!accepted!(&app, &tokens.token));
+}
+
+#[actix_rt::test]
+async fn test_bearer_tokens_need_no_csrf_token() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+    let tokens = log_in(&store).await;
+
+    let req = test::TestRequest::post()
+        .uri("/v1/tech-stacks")
+        .insert_header(bearer(&tokens.token))
+        .set_json(common::tech_stack("Rust"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+}
@@ -0,0 +1,219 @@
+mod common;
+
+use actix_web::test;
+use chrono::Duration;
+use serde_json::Value;
+
+use portfolio_backend::repository::Store;
+use portfolio_backend::services::trash_service;
+
+async fn collections_are_scoped_per_site(store: Store) {
+    let app = test::init_service(common::app(&store)).await;
+
+    for (domain, name) in [("kishor.dev", "Kishor"), ("blog.example.com", "Blog")] {
+        let req = test::TestRequest::post()
+            .uri("/v1/about")
+            .insert_header(common::bearer())
+            .set_json(common::about(domain, name))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 201);
+    }
+
+    // One admin token manages both sites, picking one with X-Site
+    let req = test::TestRequest::post()
+        .uri("/v1/tech-stacks")
+        .insert_header(common::bearer())
+        .insert_header(("X-Site", "kishor.dev"))
+        .set_json(common::tech_stack("Rust"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+    let req = test::TestRequest::post()
+        .uri("/v1/tech-stacks")
+        .insert_header(common::bearer())
+        .insert_header(("X-Site", "blog.example.com"))
+        .set_json(common::tech_stack("Go"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+    let req = test::TestRequest::post()
+        .uri("/v1/contact")
+        .insert_header(common::bearer())
+        .insert_header(("X-Site", "blog.example.com"))
+        .set_json(common::contact())
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+    // Visitors are served the site named by the Host header
+    let req = test::TestRequest::get()
+        .uri("/v1/tech-stacks")
+        .insert_header(("Host", "kishor.dev"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    let vary: Vec<_> = resp.headers().get_all("Vary").collect();
+    assert_eq!(vary, ["Host", "X-Site", "Accept-Language"]);
+    let page: Value = test::read_body_json(resp).await;
+    let names: Vec<&str> = page["items"].as_array().unwrap().iter().map(|item| item["name"].as_str().unwrap()).collect();
+    assert_eq!(names, ["Rust"]);
+
+    let req = test::TestRequest::get()
+        .uri("/v1/tech-stacks")
+        .insert_header(("Host", "blog.example.com:8080"))
+        .to_request();
+    let page: Value = test::call_and_read_body_json(&app, req).await;
+    let names: Vec<&str> = page["items"].as_array().unwrap().iter().map(|item| item["name"].as_str().unwrap()).collect();
+    assert_eq!(names, ["Go"]);
+
+    // Without DEFAULT_SITE the oldest site keeps the plain tables
+    let req = test::TestRequest::get().uri("/v1/tech-stacks").to_request();
+    let page: Value = test::call_and_read_body_json(&app, req).await;
+    let names: Vec<&str> = page["items"].as_array().unwrap().iter().map(|item| item["name"].as_str().unwrap()).collect();
+    assert_eq!(names, ["Rust"]);
+
+    let req = test::TestRequest::get()
+        .uri("/v1/contact")
+        .insert_header(("Host", "blog.example.com"))
+        .to_request();
+    let contact: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(contact.as_array().unwrap().len(), 1);
+
+    let req = test::TestRequest::get()
+        .uri("/v1/contact")
+        .insert_header(("Host", "kishor.dev"))
+        .to_request();
+    let contact: Value = test::call_and_read_body_json(&app, req).await;
+    assert!(contact.as_array().unwrap().is_empty());
+
+    let req = test::TestRequest::get()
+        .uri("/v1/about")
+        .insert_header(("Host", "blog.example.com"))
+        .to_request();
+    let current: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(current["name"], "Blog");
+}
+
+#[actix_rt::test]
+async fn test_collections_are_scoped_per_site_in_memory() {
+    collections_are_scoped_per_site(Store::in_memory()).await;
+}
+
+#[actix_rt::test]
+async fn test_collections_are_scoped_per_site_in_surreal() {
+    collections_are_scoped_per_site(common::surreal_store().await).await;
+}
+
+async fn default_site_outlives_its_about(store: Store) {
+    let app = test::init_service(common::app(&store)).await;
+
+    let mut abouts = Vec::new();
+    for (domain, name) in [("a.dev", "A"), ("b.dev", "B")] {
+        let req = test::TestRequest::post()
+            .uri("/v1/about")
+            .insert_header(common::bearer())
+            .set_json(common::about(domain, name))
+            .to_request();
+        let about: Value = test::call_and_read_body_json(&app, req).await;
+        abouts.push(about);
+
+        let req = test::TestRequest::post()
+            .uri("/v1/certificates")
+            .insert_header(common::bearer())
+            .insert_header(("X-Site", domain))
+            .set_json(common::certificate(name))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 201);
+    }
+
+    let titles = |host: &'static str| {
+        let app = &app;
+        async move {
+            let req = test::TestRequest::get()
+                .uri("/v1/certificates/all")
+                .insert_header(("Host", host))
+                .to_request();
+            let page: Value = test::call_and_read_body_json(app, req).await;
+            page["items"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|item| item["title"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        }
+    };
+
+    // Trashing the default site's About does not hand its tables to b.dev
+    let req = test::TestRequest::delete()
+        .uri(&format!("/v1/about/{}", abouts[0]["id"].as_str().unwrap()))
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", "*"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+    assert_eq!(titles("b.dev").await, ["B"]);
+    assert_eq!(titles("a.dev").await, ["A"]);
+
+    // Neither does purging it
+    assert_eq!(trash_service::purge_all(&store, Duration::zero()).await.unwrap(), 1);
+    assert_eq!(titles("b.dev").await, ["B"]);
+    assert_eq!(titles("a.dev").await, ["A"]);
+}
+
+#[actix_rt::test]
+async fn test_default_site_outlives_its_about_in_memory() {
+    default_site_outlives_its_about(Store::in_memory()).await;
+}
+
+#[actix_rt::test]
+async fn test_default_site_outlives_its_about_in_surreal() {
+    default_site_outlives_its_about(common::surreal_store().await).await;
+}
+
+#[actix_rt::test]
+async fn test_unknown_site_header_is_not_found() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    let req = test::TestRequest::get()
+        .uri("/v1/certificates/all")
+        .insert_header(("X-Site", "nowhere.example.com"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    // An unknown Host falls back to the default site
+    let req = test::TestRequest::get()
+        .uri("/v1/certificates/all")
+        .insert_header(("Host", "nowhere.example.com"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+}
+
+#[actix_rt::test]
+async fn test_admin_lists_sites() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    let req = test::TestRequest::get().uri("/v1/sites").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 401);
+
+    let mut created = Vec::new();
+    for (domain, name) in [("kishor.dev", "Kishor"), ("blog.example.com", "Blog")] {
+        let req = test::TestRequest::post()
+            .uri("/v1/about")
+            .insert_header(common::bearer())
+            .set_json(common::about(domain, name))
+            .to_request();
+        let about: Value = test::call_and_read_body_json(&app, req).await;
+        created.push(about);
+    }
+
+    let req = test::TestRequest::get()
+        .uri("/v1/sites")
+        .insert_header(common::bearer())
+        .to_request();
+    let sites: Value = test::call_and_read_body_json(&app, req).await;
+    let sites = sites.as_array().unwrap();
+    assert_eq!(sites.len(), 2);
+    assert_eq!(sites[0]["default"], true);
+    assert_eq!(sites[0]["domain"], "kishor.dev");
+    assert_eq!(sites[1]["domain"], "blog.example.com");
+    assert_eq!(sites[1]["about"], created[1]["id"]);
+}
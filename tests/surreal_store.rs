@@ -0,0 +1,79 @@
+mod common;
+
+use actix_web::test;
+use serde_json::Value;
+
+use portfolio_backend::{config, repository::Store};
+
+#[actix_rt::test]
+async fn test_tech_stack_crud_against_embedded_surreal() {
+    let store = common::surreal_store().await;
+    let app = test::init_service(common::app(&store)).await;
+
+    let req = test::TestRequest::post()
+        .uri("/v1/tech-stacks")
+        .insert_header(common::bearer())
+        .set_json(common::tech_stack("Rust"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let created: Value = test::read_body_json(resp).await;
+    let id = created["id"].as_str().expect("created record has an id").to_string();
+
+    let req = test::TestRequest::get().uri("/v1/tech-stacks").to_request();
+    let page: Value = test::call_and_read_body_json(&app, req).await;
+    let list = page["items"].as_array().unwrap();
+    assert_eq!(list.len(), 1);
+    assert_eq!(list[0]["id"], id.as_str());
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/v1/tech-stacks/{id}"))
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", "*"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+    let req = test::TestRequest::get().uri("/v1/tech-stacks").to_request();
+    let page: Value = test::call_and_read_body_json(&app, req).await;
+    let list = page["items"].as_array().unwrap();
+    assert!(list.is_empty());
+}
+
+#[actix_rt::test]
+async fn test_duplicate_slug_is_a_conflict_against_embedded_surreal() {
+    let store = common::surreal_store().await;
+    let app = test::init_service(common::app(&store)).await;
+
+    for expected in [201, 409] {
+        let req = test::TestRequest::post()
+            .uri("/v1/projects")
+            .insert_header(common::bearer())
+            .set_json(common::project("portfolio-backend"))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), expected);
+    }
+}
+
+#[actix_rt::test]
+async fn test_revisions_are_indexed_once_written() {
+    let db = common::surreal_db().await;
+    let store = Store::surreal(db.clone());
+    let app = test::init_service(common::app(&store)).await;
+
+    let req = test::TestRequest::post()
+        .uri("/v1/tech-stacks")
+        .insert_header(common::bearer())
+        .set_json(common::tech_stack("Rust"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+    let mut response = db.query("INFO FOR TABLE revisions").await.unwrap();
+    let info: Option<Value> = response.take(0).unwrap();
+    assert!(info.unwrap()["indexes"].get("record").is_some());
+}
+
+#[actix_rt::test]
+async fn test_unknown_scheme_is_rejected() {
+    let err = config::connect("ftp://example.com").await.unwrap_err();
+    assert!(err.to_string().contains("Unsupported SURREAL_URI scheme"));
+}
@@ -0,0 +1,208 @@
+mod common;
+
+use actix_web::test;
+use chrono::Duration;
+use serde_json::{json, Value};
+
+use portfolio_backend::models::project::Project;
+use portfolio_backend::models::revision::Revision;
+use portfolio_backend::models::site::Site;
+use portfolio_backend::repository::Store;
+use portfolio_backend::services::trash_service;
+
+fn project(slug: &str, title: &str) -> Value {
+    let mut project = common::project(slug);
+    project["title"] = json!(title);
+    project
+}
+
+async fn exercise_trash(store: Store) {
+    let app = test::init_service(common::app(&store)).await;
+
+    for slug in ["kept", "trashed"] {
+        let req = test::TestRequest::post()
+            .uri("/v1/projects")
+            .insert_header(common::bearer())
+            .set_json(project(slug, "Portfolio Backend"))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 201);
+    }
+
+    let req = test::TestRequest::delete()
+        .uri("/v1/projects/trashed")
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", "*"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+    // Gone from public reads and writes
+    let req = test::TestRequest::get().uri("/v1/projects/trashed").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+    let req = test::TestRequest::get().uri("/v1/projects").to_request();
+    let page: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(page["items"].as_array().unwrap().len(), 1);
+    let req = test::TestRequest::delete()
+        .uri("/v1/projects/trashed")
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", "*"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    let req = test::TestRequest::get().uri("/v1/trash").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 401);
+
+    let req = test::TestRequest::get()
+        .uri("/v1/trash")
+        .insert_header(common::bearer())
+        .to_request();
+    let trash: Vec<Value> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(trash.len(), 1);
+    assert_eq!(trash[0]["collection"], "projects");
+    assert_eq!(trash[0]["id"], "trashed");
+    assert!(trash[0]["deleted_at"].is_string());
+
+    let req = test::TestRequest::post()
+        .uri("/v1/trash/projects/trashed/restore")
+        .insert_header(common::bearer())
+        .to_request();
+    let restored: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(restored["slug"], "trashed");
+    assert!(restored.get("deleted_at").is_none());
+
+    let req = test::TestRequest::get().uri("/v1/projects/trashed").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    // Only trashed records can be restored
+    let req = test::TestRequest::post()
+        .uri("/v1/trash/projects/kept/restore")
+        .insert_header(common::bearer())
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+    let req = test::TestRequest::post()
+        .uri("/v1/trash/unknown/kept/restore")
+        .insert_header(common::bearer())
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    let req = test::TestRequest::delete()
+        .uri("/v1/projects/trashed")
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", "*"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+    // Within the retention period nothing is purged
+    assert_eq!(trash_service::purge_all(&store, Duration::days(30)).await.unwrap(), 0);
+    assert_eq!(trash_service::purge_all(&store, Duration::zero()).await.unwrap(), 1);
+
+    let req = test::TestRequest::get()
+        .uri("/v1/trash")
+        .insert_header(common::bearer())
+        .to_request();
+    let trash: Vec<Value> = test::call_and_read_body_json(&app, req).await;
+    assert!(trash.is_empty());
+}
+
+#[actix_rt::test]
+async fn test_trash_in_memory() {
+    exercise_trash(Store::in_memory()).await;
+}
+
+#[actix_rt::test]
+async fn test_trash_in_surreal() {
+    exercise_trash(common::surreal_store().await).await;
+}
+
+async fn trashed_site_is_purged_with_its_tables(store: Store) {
+    let app = test::init_service(common::app(&store)).await;
+
+    let mut abouts = Vec::new();
+    for (domain, name) in [("a.dev", "A"), ("b.dev", "B")] {
+        let req = test::TestRequest::post()
+            .uri("/v1/about")
+            .insert_header(common::bearer())
+            .set_json(common::about(domain, name))
+            .to_request();
+        let about: Value = test::call_and_read_body_json(&app, req).await;
+        abouts.push(about["id"].as_str().unwrap().to_string());
+    }
+
+    for slug in ["kept", "trashed"] {
+        let req = test::TestRequest::post()
+            .uri("/v1/projects")
+            .insert_header(common::bearer())
+            .insert_header(("X-Site", "b.dev"))
+            .set_json(project(slug, slug))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 201);
+    }
+    let req = test::TestRequest::delete()
+        .uri("/v1/projects/trashed")
+        .insert_header(common::bearer())
+        .insert_header(("X-Site", "b.dev"))
+        .insert_header(("If-Match", "*"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/v1/about/{}", abouts[1]))
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", "*"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+    // The trashed site's trash is purged along with its About record, and
+    // nothing is left behind in its tables
+    assert_eq!(trash_service::purge_all(&store, Duration::zero()).await.unwrap(), 2);
+    let site = Site::scoped("b.dev".to_string(), abouts[1].clone());
+    assert!(store.repository_for::<Project>(&site).list().await.unwrap().is_empty());
+    assert!(store.repository_for::<Revision>(&site).list().await.unwrap().is_empty());
+
+    // The default site keeps its tables and its history
+    let revisions = store.repository::<Revision>().list().await.unwrap();
+    assert!(revisions.iter().any(|revision| revision.record == abouts[0]));
+}
+
+#[actix_rt::test]
+async fn test_trashed_site_is_purged_with_its_tables_in_memory() {
+    trashed_site_is_purged_with_its_tables(Store::in_memory()).await;
+}
+
+#[actix_rt::test]
+async fn test_trashed_site_is_purged_with_its_tables_in_surreal() {
+    trashed_site_is_purged_with_its_tables(common::surreal_store().await).await;
+}
+
+#[actix_rt::test]
+async fn test_creating_over_a_trashed_record_is_a_conflict() {
+    let store = Store::in_memory();
+    let app = test::init_service(common::app(&store)).await;
+
+    let req = test::TestRequest::post()
+        .uri("/v1/projects")
+        .insert_header(common::bearer())
+        .set_json(project("reused", "First"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+    let req = test::TestRequest::delete()
+        .uri("/v1/projects/reused")
+        .insert_header(common::bearer())
+        .insert_header(("If-Match", "*"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+    let req = test::TestRequest::post()
+        .uri("/v1/projects")
+        .insert_header(common::bearer())
+        .set_json(project("reused", "Second"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 409);
+
+    let req = test::TestRequest::post()
+        .uri("/v1/trash/projects/reused/restore")
+        .insert_header(common::bearer())
+        .to_request();
+    let restored: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(restored["title"], "First");
+}